@@ -1,4 +1,3 @@
-use std::io;
 use std::io::BufRead;
 use std::io::BufReader;
 use std::fs::File;
@@ -6,15 +5,16 @@ use std::mem;
 
 use jp_term::Colour;
 use jp_term::Target;
-use jp_term::ansi::AnsiTarget;
 use jp_term::border_box::BoxStyle;
+use jp_term::plain::AutoTarget;
 use jp_term::table::Table;
 use jp_term::table::TableBox;
 
 fn main () -> anyhow::Result <()> {
 	let cryptos = read () ?;
-	let mut target = AnsiTarget::new (io::stdout (), 80);
+	let mut target = AutoTarget::stdout ();
 	render (& mut target, & cryptos);
+	target.finish () ?;
 	Ok (())
 }
 
@@ -1,18 +1,114 @@
 use std::borrow::Cow;
-use std::io::Write;
+use std::env;
+use std::fmt::{ self, Write as _ };
+use std::io::{ self, Write };
 
-use crate::{ Attr, Colour, Target };
+use crate::{ Attr, Colour, Target, TermColour, Underline };
 
 pub struct AnsiTarget <Out: Write> {
 	out: Out,
 	width: usize,
+	colour_depth: ColourDepth,
+	current: Option <Attr>,
+	hyperlinks: bool,
+	error: Option <io::Error>,
 }
 
 impl <Out: Write> AnsiTarget <Out> {
 
 	#[ inline ]
 	pub fn new (out: Out, width: usize) -> Self {
-		Self { out, width }
+		Self {
+			out,
+			width,
+			colour_depth: ColourDepth::TrueColour,
+			current: None,
+			hyperlinks: true,
+			error: None,
+		}
+	}
+
+	#[ inline ]
+	pub fn with_colour_depth (mut self, colour_depth: ColourDepth) -> Self {
+		self.colour_depth = colour_depth;
+		self
+	}
+
+	#[ inline ]
+	pub fn with_hyperlinks (mut self, hyperlinks: bool) -> Self {
+		self.hyperlinks = hyperlinks;
+		self
+	}
+
+	#[ inline ]
+	pub fn colour_depth (& self) -> ColourDepth {
+		self.colour_depth
+	}
+
+	#[ inline ]
+	pub fn error (& self) -> Option <& io::Error> {
+		self.error.as_ref ()
+	}
+
+	pub fn finish (mut self) -> io::Result <Out> {
+		if let Some (err) = self.error.take () { return Err (err) }
+		self.out.flush () ?;
+		Ok (self.out)
+	}
+
+	fn write_fmt (& mut self, args: fmt::Arguments) {
+		if self.error.is_some () { return }
+		if let Err (err) = self.out.write_fmt (args) {
+			self.error = Some (err);
+		}
+	}
+
+}
+
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
+pub enum ColourDepth {
+	TrueColour,
+	Ansi256,
+	Ansi16,
+	Monochrome,
+}
+
+impl ColourDepth {
+
+	#[ inline ]
+	pub fn detect () -> Self {
+		Self::detect_with (|name| env::var (name).ok ())
+	}
+
+	pub fn detect_with (get_var: impl Fn (& str) -> Option <String>) -> Self {
+		let is_set = |name| get_var (name).is_some_and (|value| ! value.is_empty ());
+		if is_set ("NO_COLOR") { return Self::Monochrome }
+		let force = get_var ("CLICOLOR_FORCE").is_some_and (|value| ! value.is_empty () && value != "0");
+		let colour_term = get_var ("COLORTERM").unwrap_or_default ();
+		if colour_term == "truecolor" || colour_term == "24bit" { return Self::TrueColour }
+		let term = get_var ("TERM").unwrap_or_default ();
+		if term.ends_with ("-direct") { return Self::TrueColour }
+		if term.contains ("256color") { return Self::Ansi256 }
+		if term.is_empty () || term == "dumb" {
+			return if force { Self::Ansi16 } else { Self::Monochrome };
+		}
+		Self::Ansi16
+	}
+
+	pub fn downgrade_colour (self, colour: TermColour) -> Option <TermColour> {
+		Some (match (self, colour) {
+			(Self::Monochrome, _) => return None,
+			(Self::TrueColour, _) | (_, TermColour::Ansi (_)) => colour,
+			(Self::Ansi256, _) => TermColour::Indexed (colour.to_ansi_256 ()),
+			(Self::Ansi16, _) => TermColour::Ansi (colour.to_ansi_16 ()),
+		})
+	}
+
+	pub fn downgrade (self, mut attr: Attr) -> Attr {
+		attr.foreground = attr.foreground.and_then (|colour| self.downgrade_colour (colour));
+		attr.background = attr.background.and_then (|colour| self.downgrade_colour (colour));
+		attr.underline_colour = attr.underline_colour.and_then (|colour| self.downgrade_colour (colour));
+		attr
 	}
 
 }
@@ -24,33 +120,266 @@ impl <'dat, Out: Write> Target <'dat> for AnsiTarget <Out> {
 		self.width
 	}
 
-	#[ inline ]
 	fn push_attr (& mut self, attr: Attr) {
-		if let Some (Colour { red, green, blue }) = attr.foreground {
-			write! (self.out, "\x1b[38;2;{red};{green};{blue}m").unwrap ();
-		} else {
-			write! (self.out, "\x1b[39m").unwrap ();
-		}
-		if let Some (Colour { red, green, blue }) = attr.background {
-			write! (self.out, "\x1b[48;2;{red};{green};{blue}m").unwrap ();
-		} else {
-			write! (self.out, "\x1b[49m").unwrap ();
-		}
-		if attr.bold {
-			write! (self.out, "\x1b[1m").unwrap ();
-		} else {
-			write! (self.out, "\x1b[22m").unwrap ();
-		}
+		let attr = self.colour_depth.downgrade (attr);
+		if self.current == Some (attr) { return }
+		let mut reset_params = vec! [ "0".to_owned () ];
+		attr_params (& mut reset_params, self.colour_depth, Attr::default (), attr);
+		let params = match self.current {
+			Some (current) => {
+				let mut delta_params = Vec::new ();
+				attr_params (& mut delta_params, self.colour_depth, current, attr);
+				if delta_params.concat ().len () <= reset_params.concat ().len () { delta_params } else { reset_params }
+			},
+			None => reset_params,
+		};
+		self.current = Some (attr);
+		if params.is_empty () { return }
+		write! (self, "\x1b[{}m", params.join (";"));
 	}
 
 	#[ inline ]
 	fn push_str (& mut self, text: Cow <'dat, str>) {
-		write! (self.out, "{text}").unwrap ();
+		write! (self, "{text}");
 	}
 
 	#[ inline ]
 	fn newline (& mut self) {
-		write! (self.out, "\n").unwrap ();
+		writeln! (self);
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		if ! self.hyperlinks { return }
+		write! (self, "\x1b]8;;{}\x1b\\", encode_url (& url));
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+		if ! self.hyperlinks { return }
+		write! (self, "\x1b]8;;\x1b\\");
+	}
+
+}
+
+fn attr_params (params: & mut Vec <String>, colour_depth: ColourDepth, from: Attr, to: Attr) {
+	if from.foreground != to.foreground {
+		colour_params (params, colour_depth, ColourSlot::Foreground, to.foreground);
+	}
+	if from.background != to.background {
+		colour_params (params, colour_depth, ColourSlot::Background, to.background);
+	}
+	if from.underline_colour != to.underline_colour {
+		colour_params (params, colour_depth, ColourSlot::Underline, to.underline_colour);
+	}
+	if (from.bold && ! to.bold) || (from.dim && ! to.dim) {
+		params.push ("22".to_owned ());
+		if to.bold { params.push ("1".to_owned ()) }
+		if to.dim { params.push ("2".to_owned ()) }
+	} else {
+		if to.bold && ! from.bold { params.push ("1".to_owned ()) }
+		if to.dim && ! from.dim { params.push ("2".to_owned ()) }
+	}
+	let mut flag = |from: bool, to: bool, on: & str, off: & str| {
+		if from != to { params.push ((if to { on } else { off }).to_owned ()) }
+	};
+	flag (from.italic, to.italic, "3", "23");
+	flag (from.blink, to.blink, "5", "25");
+	flag (from.reverse, to.reverse, "7", "27");
+	flag (from.hidden, to.hidden, "8", "28");
+	flag (from.strikethrough, to.strikethrough, "9", "29");
+	if from.underline != to.underline {
+		params.push (underline_param (to.underline).to_owned ());
+	}
+}
+
+#[ derive (Clone, Copy) ]
+enum ColourSlot {
+	Foreground,
+	Background,
+	Underline,
+}
+
+fn colour_params (
+	params: & mut Vec <String>,
+	colour_depth: ColourDepth,
+	slot: ColourSlot,
+	colour: Option <TermColour>,
+) {
+	let (base, reset) = match slot {
+		ColourSlot::Foreground => (38, 39),
+		ColourSlot::Background => (48, 49),
+		ColourSlot::Underline => (58, 59),
+	};
+	let Some (colour) = colour else {
+		if colour_depth != ColourDepth::Monochrome { params.push (format! ("{reset}")) }
+		return;
+	};
+	match (colour, slot) {
+		(TermColour::Rgb (Colour { red, green, blue }), _) => {
+			params.push (format! ("{base};2;{red};{green};{blue}"));
+		},
+		(TermColour::Indexed (index), _) => {
+			params.push (format! ("{base};5;{index}"));
+		},
+		(TermColour::Ansi (ansi), ColourSlot::Underline) => {
+			params.push (format! ("{base};5;{}", ansi.index ()));
+		},
+		(TermColour::Ansi (ansi), _) => {
+			let index = ansi.index ();
+			let code = if index < 8 { base - 8 + index } else { base + 52 + index - 8 };
+			params.push (format! ("{code}"));
+		},
+	}
+}
+
+fn encode_url (url: & str) -> Cow <'_, str> {
+	if url.bytes ().all (|byte| (0x21 ..= 0x7e).contains (& byte)) { return Cow::Borrowed (url) }
+	let mut encoded = String::with_capacity (url.len ());
+	for byte in url.bytes () {
+		if (0x21 ..= 0x7e).contains (& byte) {
+			encoded.push (byte as char);
+		} else {
+			write! (encoded, "%{byte:02X}").unwrap ();
+		}
+	}
+	Cow::Owned (encoded)
+}
+
+fn underline_param (underline: Underline) -> & 'static str {
+	match underline {
+		Underline::None => "24",
+		Underline::Single => "4",
+		Underline::Double => "4:2",
+		Underline::Curly => "4:3",
+		Underline::Dotted => "4:4",
+		Underline::Dashed => "4:5",
+	}
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::{ AnsiColour, Link };
+
+	#[ test ]
+	fn write_error () {
+		struct Closed;
+		impl Write for Closed {
+			fn write (& mut self, _buf: & [u8]) -> io::Result <usize> {
+				Err (io::ErrorKind::BrokenPipe.into ())
+			}
+			fn flush (& mut self) -> io::Result <()> {
+				Ok (())
+			}
+		}
+		let mut target = AnsiTarget::new (Closed, 80);
+		target.push_str (Cow::Borrowed ("hello"));
+		target.newline ();
+		assert_eq! (Some (io::ErrorKind::BrokenPipe), target.error ().map (io::Error::kind));
+		assert_eq! (io::ErrorKind::BrokenPipe, target.finish ().err ().unwrap ().kind ());
+	}
+
+	#[ test ]
+	fn hyperlinks () {
+		let mut target = AnsiTarget::new (Vec::new (), 80);
+		target.push (Link::new ("https://example.com/", "example"));
+		assert_eq! (
+			"\x1b]8;;https://example.com/\x1b\\example\x1b]8;;\x1b\\",
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ());
+		let mut target = AnsiTarget::new (Vec::new (), 80).with_hyperlinks (false);
+		target.push (Link::new ("https://example.com/", "example"));
+		assert_eq! ("example", String::from_utf8 (target.finish ().unwrap ()).unwrap ());
+		let mut target = AnsiTarget::new (Vec::new (), 80);
+		target.push (Link::new ("file:///tmp/a b/日本\x1b\\x", "file"));
+		assert_eq! (
+			"\x1b]8;;file:///tmp/a%20b/%E6%97%A5%E6%9C%AC%1B\\x\x1b\\file\x1b]8;;\x1b\\",
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ());
+	}
+
+	#[ test ]
+	fn push_attr_delta () {
+		let red = Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00));
+		let mut target = AnsiTarget::new (Vec::new (), 80);
+		target.push_attr (Attr::default ());
+		target.push_attr (Attr::default ());
+		target.push_str (Cow::Borrowed ("a"));
+		target.push_attr (red);
+		target.push_attr (red);
+		target.push_str (Cow::Borrowed ("b"));
+		target.push_attr (red.bold (true).italic (true));
+		target.push_str (Cow::Borrowed ("c"));
+		target.push_attr (red.italic (true));
+		target.push_str (Cow::Borrowed ("d"));
+		target.push_attr (Attr::default ());
+		target.push_attr (Attr::default ().dim (true));
+		target.push_attr (Attr::default ());
+		assert_eq! (
+			"\x1b[0ma\x1b[38;2;255;0;0mb\x1b[1;3mc\x1b[22md\x1b[0m\x1b[2m\x1b[0m",
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ());
+	}
+
+	#[ test ]
+	fn push_attr_colour_depth () {
+		let attr = Attr::default ()
+			.foreground (Colour::new (0xff, 0x00, 0x00))
+			.background (Colour::new (0x00, 0x00, 0xc0))
+			.bold (true);
+		let render = |colour_depth| {
+			let mut target = AnsiTarget::new (Vec::new (), 80).with_colour_depth (colour_depth);
+			target.push_attr (attr);
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ()
+		};
+		assert_eq! ("\x1b[0;38;5;196;48;5;19;1m", render (ColourDepth::Ansi256));
+		assert_eq! ("\x1b[0;91;44;1m", render (ColourDepth::Ansi16));
+		assert_eq! ("\x1b[0;1m", render (ColourDepth::Monochrome));
+	}
+
+	#[ test ]
+	fn push_attr_term_colours () {
+		let attr = Attr::default ()
+			.foreground_term (TermColour::Ansi (AnsiColour::BrightRed))
+			.background_term (TermColour::Indexed (19))
+			.underline_colour_term (TermColour::Ansi (AnsiColour::Green));
+		let render = |colour_depth| {
+			let mut target = AnsiTarget::new (Vec::new (), 80).with_colour_depth (colour_depth);
+			target.push_attr (attr);
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ()
+		};
+		assert_eq! ("\x1b[0;91;48;5;19;58;5;2m", render (ColourDepth::TrueColour));
+		assert_eq! ("\x1b[0;91;48;5;19;58;5;2m", render (ColourDepth::Ansi256));
+		assert_eq! ("\x1b[0;91;44;58;5;2m", render (ColourDepth::Ansi16));
+		assert_eq! ("\x1b[0m", render (ColourDepth::Monochrome));
+	}
+
+	#[ test ]
+	fn colour_depth_detect () {
+		let detect = |vars: & [(& str, & str)]| ColourDepth::detect_with (|name| {
+			vars.iter ().find (|& & (key, _)| key == name).map (|& (_, value)| value.to_owned ())
+		});
+		assert_eq! (ColourDepth::TrueColour, detect (& [ ("COLORTERM", "truecolor"), ("TERM", "xterm") ]));
+		assert_eq! (ColourDepth::Ansi256, detect (& [ ("TERM", "screen-256color") ]));
+		assert_eq! (ColourDepth::Ansi16, detect (& [ ("TERM", "xterm") ]));
+		assert_eq! (ColourDepth::Monochrome, detect (& [ ("TERM", "dumb") ]));
+		assert_eq! (ColourDepth::Ansi16, detect (& [ ("TERM", "dumb"), ("CLICOLOR_FORCE", "1") ]));
+		assert_eq! (ColourDepth::Monochrome, detect (& [ ("COLORTERM", "truecolor"), ("NO_COLOR", "1") ]));
+		assert_eq! (ColourDepth::Monochrome, detect (& []));
+	}
+
+	#[ test ]
+	fn push_attr_modifiers () {
+		let mut target = AnsiTarget::new (Vec::new (), 80);
+		target.push_attr (Attr::default ()
+			.foreground (Colour::new (1, 2, 3))
+			.underline (Underline::Curly)
+			.underline_colour (Colour::new (4, 5, 6))
+			.bold (true)
+			.italic (true)
+			.strikethrough (true));
+		assert_eq! (
+			"\x1b[0;38;2;1;2;3;58;2;4;5;6;1;3;9;4:3m",
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ());
 	}
 
 }
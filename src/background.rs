@@ -0,0 +1,188 @@
+use std::io::{ self, Write };
+use std::time::{ Duration, Instant };
+
+use crate::Colour;
+use crate::colour::ANSI_16_PALETTE;
+
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
+pub struct Background {
+	pub colour: Colour,
+	pub brightness: Brightness,
+}
+
+impl Background {
+
+	#[ inline ]
+	pub fn new (colour: Colour) -> Self {
+		Self { colour, brightness: Brightness::of (colour) }
+	}
+
+}
+
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
+pub enum Brightness {
+	Light,
+	Dark,
+}
+
+impl Brightness {
+
+	pub fn of (colour: Colour) -> Self {
+		if colour.readable_foreground () == Colour::new (0x00, 0x00, 0x00) { Self::Light } else { Self::Dark }
+	}
+
+}
+
+pub trait QueryInput {
+	fn read_timeout (& mut self, buf: & mut [u8], timeout: Duration) -> io::Result <usize>;
+}
+
+impl QueryInput for & [u8] {
+
+	fn read_timeout (& mut self, buf: & mut [u8], _timeout: Duration) -> io::Result <usize> {
+		let len = buf.len ().min (self.len ());
+		buf [.. len].copy_from_slice (& self [.. len]);
+		* self = & self [len .. ];
+		Ok (len)
+	}
+
+}
+
+#[ cfg (unix) ]
+pub struct StdinInput;
+
+#[ cfg (unix) ]
+impl QueryInput for StdinInput {
+
+	fn read_timeout (& mut self, buf: & mut [u8], timeout: Duration) -> io::Result <usize> {
+		let mut poll_fd = libc::pollfd { fd: libc::STDIN_FILENO, events: libc::POLLIN, revents: 0 };
+		let millis = timeout.as_millis ().min (i32::MAX as u128) as i32;
+		// SAFETY: poll_fd is a valid, exclusively borrowed pollfd and nfds is 1
+		let ready = unsafe { libc::poll (& mut poll_fd, 1, millis) };
+		if ready < 0 { return Err (io::Error::last_os_error ()) }
+		if ready == 0 { return Ok (0) }
+		// SAFETY: buf is a valid, exclusively borrowed buffer of buf.len () writable bytes
+		let len = unsafe { libc::read (libc::STDIN_FILENO, buf.as_mut_ptr ().cast (), buf.len ()) };
+		if len < 0 { return Err (io::Error::last_os_error ()) }
+		Ok (len as usize)
+	}
+
+}
+
+const QUERY: & [u8] = b"\x1b]11;?\x1b\\\x1b[c";
+const GRACE: Duration = Duration::from_millis (500);
+const MAX_RESPONSE: usize = 4096;
+
+// Reads until the DA1 reply which every terminal sends after the OSC 11 reply, allowing a grace
+// period past the timeout so a late reply is not left in stdin. Any typeahead is consumed.
+pub fn query_background (
+	input: & mut impl QueryInput,
+	output: & mut impl Write,
+	timeout: Duration,
+) -> io::Result <Option <Colour>> {
+	output.write_all (QUERY) ?;
+	output.flush () ?;
+	let deadline = Instant::now () + timeout + GRACE;
+	let mut response = Vec::new ();
+	while ! has_device_attributes (& response) && response.len () < MAX_RESPONSE {
+		let remaining = deadline.saturating_duration_since (Instant::now ());
+		if remaining.is_zero () { break }
+		let mut buf = [0; 64];
+		let len = input.read_timeout (& mut buf, remaining) ?;
+		if len == 0 { break }
+		response.extend_from_slice (& buf [.. len]);
+	}
+	Ok (parse_osc_11 (& response))
+}
+
+fn has_device_attributes (response: & [u8]) -> bool {
+	find (response, b"\x1b[?")
+		.is_some_and (|start| response [start .. ].contains (& b'c'))
+}
+
+fn parse_osc_11 (response: & [u8]) -> Option <Colour> {
+	let start = find (response, b"\x1b]11;") ? + 5;
+	let body = & response [start .. ];
+	let end = body.iter ().position (|& byte| byte == 0x07 || byte == 0x1b) ?;
+	let body = std::str::from_utf8 (& body [.. end]).ok () ?;
+	let body = body.strip_prefix ("rgb:").or_else (|| body.strip_prefix ("rgba:")) ?;
+	let mut parts = body.split ('/').map (|part| {
+		if part.is_empty () || part.len () > 4 { return None }
+		let value = u32::from_str_radix (part, 16).ok () ?;
+		let max = (1 << (part.len () * 4)) - 1;
+		Some (((value * 255 + max / 2) / max) as u8)
+	});
+	Some (Colour::new (parts.next () ?? , parts.next () ?? , parts.next () ??))
+}
+
+pub fn parse_colorfgbg (value: & str) -> Option <Colour> {
+	let index: u8 = value.rsplit (';').next () ?.trim ().parse ().ok () ?;
+	Some (ANSI_16_PALETTE.get (index as usize).copied ().unwrap_or_else (|| Colour::from_ansi_256 (index)))
+}
+
+fn find (haystack: & [u8], needle: & [u8]) -> Option <usize> {
+	haystack.windows (needle.len ()).position (|window| window == needle)
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+
+	const TIMEOUT: Duration = Duration::from_millis (100);
+
+	fn query (response: & [u8]) -> (Option <Colour>, Vec <u8>) {
+		let mut input = response;
+		let mut output = Vec::new ();
+		let colour = query_background (& mut input, & mut output, TIMEOUT).unwrap ();
+		(colour, output)
+	}
+
+	#[ test ]
+	fn query_osc_11 () {
+		let (colour, output) = query (b"\x1b]11;rgb:ffff/ffff/ffff\x1b\\\x1b[?62;22c");
+		assert_eq! (QUERY, output.as_slice ());
+		assert_eq! (Some (Colour::new (0xff, 0xff, 0xff)), colour);
+		assert_eq! (Some (Colour::new (0x1e, 0x1e, 0x1e)), query (b"\x1b]11;rgb:1e1e/1e1e/1e1e\x07\x1b[?1;2c").0);
+		assert_eq! (Some (Colour::new (0x12, 0x34, 0x56)), query (b"\x1b]11;rgba:12/34/56/ff\x07").0);
+		assert_eq! (Some (Colour::new (0x11, 0x88, 0xff)), query (b"\x1b]11;rgb:1/8/f\x07").0);
+		assert_eq! (None, query (b"\x1b[?1;2c").0);
+		assert_eq! (None, query (b"\x1b]11;rgb:ffff/ffff\x07").0);
+		assert_eq! (None, query (b"").0);
+	}
+
+	#[ test ]
+	fn query_split_reads () {
+		struct Chunks (Vec <& 'static [u8]>);
+		impl QueryInput for Chunks {
+			fn read_timeout (& mut self, buf: & mut [u8], _timeout: Duration) -> io::Result <usize> {
+				if self.0.is_empty () { return Ok (0) }
+				let chunk = self.0.remove (0);
+				buf [.. chunk.len ()].copy_from_slice (chunk);
+				Ok (chunk.len ())
+			}
+		}
+		let mut input = Chunks (vec! [ b"x\x1b]11;rgb:1e1e/", b"1e1e/1e1e\x1b\\", b"\x1b[?62;", b"22c", b"typed" ]);
+		let colour = query_background (& mut input, & mut Vec::new (), TIMEOUT).unwrap ();
+		assert_eq! (Some (Colour::new (0x1e, 0x1e, 0x1e)), colour);
+		assert_eq! (vec! [ b"typed" as & [u8] ], input.0);
+	}
+
+	#[ test ]
+	fn colorfgbg () {
+		assert_eq! (Some (Colour::new (0x00, 0x00, 0x00)), parse_colorfgbg ("15;0"));
+		assert_eq! (Some (Colour::new (0xff, 0xff, 0xff)), parse_colorfgbg ("0;default;15"));
+		assert_eq! (Some (Colour::new (0xee, 0xee, 0xee)), parse_colorfgbg ("0;255"));
+		assert_eq! (None, parse_colorfgbg ("15;default"));
+		assert_eq! (None, parse_colorfgbg (""));
+	}
+
+	#[ test ]
+	fn brightness () {
+		assert_eq! (Brightness::Light, Brightness::of (Colour::new (0xff, 0xff, 0xff)));
+		assert_eq! (Brightness::Light, Brightness::of (Colour::new (0xfd, 0xf6, 0xe3)));
+		assert_eq! (Brightness::Dark, Brightness::of (Colour::new (0x1e, 0x1e, 0x1e)));
+		assert_eq! (Brightness::Dark, Brightness::of (Colour::new (0x00, 0x2b, 0x36)));
+	}
+
+}
@@ -1,8 +1,6 @@
-use std::iter;
-
 use crate::{ Attr, Colour, Printable, Target };
 
-#[ derive (Clone, Copy) ]
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
 pub struct BoxStyle {
 	pub surround: Colour,
 	pub background: Colour,
@@ -37,6 +35,12 @@ impl BoxStyle {
 
 }
 
+#[ cfg (test) ]
+pub const TEST_BOX_STYLE: BoxStyle = BoxStyle::new (
+	Colour::new (0x00, 0x00, 0x00),
+	Colour::new (0x50, 0x40, 0x30),
+	Colour::new (0xff, 0xff, 0xff));
+
 #[ derive (Clone, Copy) ]
 pub struct BorderBox {
 	pub box_style: BoxStyle,
@@ -87,6 +91,19 @@ pub enum BorderType {
 	Middle,
 }
 
+impl BorderType {
+
+	#[ inline ]
+	pub const fn symbol (self) -> & 'static str {
+		match self {
+			Self::Top => Border::SYM_TOP,
+			Self::Bottom => Border::SYM_BOTTOM,
+			Self::Middle => Border::SYM_MIDDLE,
+		}
+	}
+
+}
+
 impl Border {
 
 	const SYM_TOP: & 'static str = "▅";
@@ -120,13 +137,12 @@ impl <'dat> Printable <'dat> for Border {
 
 	fn print (self, target: & mut impl Target <'dat>) {
 		let Self { type_, background, foreground, width } = self;
-		let (background, foreground, ch) = match type_ {
-			BorderType::Top => (background, foreground, Self::SYM_TOP),
-			BorderType::Bottom => (background, foreground, Self::SYM_BOTTOM),
-			BorderType::Middle => (foreground, background, Self::SYM_MIDDLE),
+		let (background, foreground) = match type_ {
+			BorderType::Top | BorderType::Bottom => (background, foreground),
+			BorderType::Middle => (foreground, background),
 		};
 		target.push (Attr::default ().background (background).foreground (foreground));
-		target.push (iter::repeat (ch).take (width).collect::<String> ());
+		target.push (type_.symbol ().repeat (width));
 		target.push (Attr::default ());
 	}
 
@@ -0,0 +1,147 @@
+use std::borrow::Cow;
+use std::mem;
+
+use crate::{ Attr, Printable, Target };
+use crate::clip::{ ClipTarget, Overflow };
+use crate::width;
+
+pub type Render <'dat> = Box <dyn FnOnce (& mut BufferTarget <'dat>) + 'dat>;
+
+#[ inline ]
+pub fn render <'dat> (value: impl Printable <'dat> + 'dat) -> Render <'dat> {
+	Box::new (move |target| target.push (value))
+}
+
+#[ derive (Clone, Debug) ]
+pub enum Event <'dat> {
+	Attr (Attr),
+	Text (Cow <'dat, str>),
+	BeginLink (Cow <'dat, str>),
+	EndLink,
+}
+
+#[ derive (Clone, Debug, Default) ]
+pub struct BufferLine <'dat> {
+	events: Vec <Event <'dat>>,
+	width: usize,
+}
+
+impl <'dat> BufferLine <'dat> {
+
+	#[ inline ]
+	pub fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	pub fn is_empty (& self) -> bool {
+		self.events.is_empty ()
+	}
+
+	#[ inline ]
+	pub fn replay (self, target: & mut impl Target <'dat>) {
+		self.replay_with (target, |attr| attr);
+	}
+
+	pub fn replay_with (self, target: & mut impl Target <'dat>, map_attr: impl Fn (Attr) -> Attr) {
+		for event in self.events {
+			match event {
+				Event::Attr (attr) => target.push_attr (map_attr (attr)),
+				Event::Text (text) => target.push_str (text),
+				Event::BeginLink (url) => target.begin_link (url),
+				Event::EndLink => target.end_link (),
+			}
+		}
+	}
+
+}
+
+pub struct BufferTarget <'dat> {
+	width: usize,
+	lines: Vec <BufferLine <'dat>>,
+	line: BufferLine <'dat>,
+}
+
+impl <'dat> BufferTarget <'dat> {
+
+	#[ inline ]
+	pub fn new (width: usize) -> Self {
+		Self {
+			width,
+			lines: Vec::new (),
+			line: BufferLine::default (),
+		}
+	}
+
+	pub fn render_clipped (width: usize, render: Render <'dat>) -> Vec <BufferLine <'dat>> {
+		let mut buffer = Self::new (width);
+		let mut clip_target = ClipTarget::new (& mut buffer, Overflow::Clip);
+		clip_target.push (BufferRender (render));
+		clip_target.finish ();
+		buffer.into_lines ()
+	}
+
+	pub fn into_lines (mut self) -> Vec <BufferLine <'dat>> {
+		if ! self.line.is_empty () {
+			self.lines.push (mem::take (& mut self.line));
+		}
+		self.lines
+	}
+
+	pub fn replay (self, target: & mut impl Target <'dat>) {
+		let lines = self.into_lines ();
+		let num_lines = lines.len ();
+		for (idx, line) in lines.into_iter ().enumerate () {
+			line.replay (target);
+			if idx + 1 < num_lines { target.newline () }
+		}
+	}
+
+}
+
+impl <'dat> Target <'dat> for BufferTarget <'dat> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.line.events.push (Event::Attr (attr));
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		self.line.width += width::str_width (& text);
+		self.line.events.push (Event::Text (text));
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.lines.push (mem::take (& mut self.line));
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		self.line.events.push (Event::BeginLink (url));
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+		self.line.events.push (Event::EndLink);
+	}
+
+}
+
+struct BufferRender <'dat> (Render <'dat>);
+
+impl <'dat> Printable <'dat> for BufferRender <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let mut buffer = BufferTarget::new (target.width ());
+		(self.0) (& mut buffer);
+		buffer.replay (target);
+	}
+
+}
@@ -0,0 +1,293 @@
+use std::borrow::Cow;
+use std::mem;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{ Attr, Printable, Target };
+use crate::buffer::Event;
+use crate::width;
+
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
+pub enum Overflow {
+	Clip,
+	Wrap,
+}
+
+pub struct ClipTarget <'tar, 'dat, Inner: Target <'dat>> {
+	inner: & 'tar mut Inner,
+	overflow: Overflow,
+	ellipsis: Option <Cow <'dat, str>>,
+	line: Vec <Event <'dat>>,
+	attr: Attr,
+	link: Option <Cow <'dat, str>>,
+}
+
+impl <'tar, 'dat, Inner: Target <'dat>> ClipTarget <'tar, 'dat, Inner> {
+
+	#[ inline ]
+	pub fn new (inner: & 'tar mut Inner, overflow: Overflow) -> Self {
+		Self {
+			inner,
+			overflow,
+			ellipsis: None,
+			line: Vec::new (),
+			attr: Attr::default (),
+			link: None,
+		}
+	}
+
+	#[ inline ]
+	pub fn with_ellipsis (mut self, ellipsis: impl Into <Cow <'dat, str>>) -> Self {
+		self.ellipsis = Some (ellipsis.into ());
+		self
+	}
+
+	#[ inline ]
+	pub fn finish (mut self) {
+		self.flush_line ();
+	}
+
+	fn flush_line (& mut self) {
+		let line = mem::take (& mut self.line);
+		if line.is_empty () { return }
+		let max_width = self.inner.width ();
+		let line_width: usize = line.iter ()
+			.map (|event| match event {
+				Event::Text (text) => width::str_width (text),
+				_ => 0,
+			})
+			.sum ();
+		if line_width <= max_width {
+			for event in line { self.replay (event) }
+			return;
+		}
+		match self.overflow {
+			Overflow::Clip => self.flush_clip (line, max_width),
+			Overflow::Wrap => self.flush_wrap (line, max_width),
+		}
+	}
+
+	fn flush_clip (& mut self, line: Vec <Event <'dat>>, max_width: usize) {
+		let ellipsis = self.ellipsis.clone ()
+			.filter (|ellipsis| width::str_width (ellipsis) <= max_width);
+		let limit = max_width - ellipsis.as_deref ().map_or (0, width::str_width);
+		let mut col = 0;
+		let mut cut = false;
+		for event in line {
+			let Event::Text (text) = event else {
+				self.replay (event);
+				continue;
+			};
+			if cut { continue }
+			let (split_pos, split_width) = width::split_at_width (& text, limit - col);
+			if split_pos == text.len () {
+				col += split_width;
+				self.inner.push_str (text);
+				continue;
+			}
+			if split_pos > 0 {
+				self.inner.push_str (slice_cow (text, split_pos));
+			}
+			if let Some (ellipsis) = ellipsis.clone () {
+				self.inner.push_str (ellipsis);
+			}
+			cut = true;
+		}
+	}
+
+	fn flush_wrap (& mut self, line: Vec <Event <'dat>>, max_width: usize) {
+		let mut col = 0;
+		for event in line {
+			let Event::Text (mut text) = event else {
+				self.replay (event);
+				continue;
+			};
+			loop {
+				let (mut split_pos, split_width) = width::split_at_width (& text, max_width - col);
+				if split_pos == text.len () {
+					col += split_width;
+					self.inner.push_str (text);
+					break;
+				}
+				if split_pos == 0 && col == 0 {
+					split_pos = text.graphemes (true).next ().map_or (text.len (), str::len);
+				}
+				if split_pos > 0 {
+					let rest = text [split_pos .. ].to_owned ();
+					self.inner.push_str (slice_cow (text, split_pos));
+					text = Cow::Owned (rest);
+				}
+				self.wrap_line ();
+				col = 0;
+				if text.is_empty () { break }
+			}
+		}
+	}
+
+	fn wrap_line (& mut self) {
+		if self.link.is_some () { self.inner.end_link () }
+		self.inner.push_attr (Attr::default ());
+		self.inner.newline ();
+		self.inner.push_attr (self.attr);
+		if let Some (url) = self.link.clone () { self.inner.begin_link (url) }
+	}
+
+	fn replay (& mut self, event: Event <'dat>) {
+		match event {
+			Event::Attr (attr) => {
+				self.attr = attr;
+				self.inner.push_attr (attr);
+			},
+			Event::Text (text) => self.inner.push_str (text),
+			Event::BeginLink (url) => {
+				self.link = Some (url.clone ());
+				self.inner.begin_link (url);
+			},
+			Event::EndLink => {
+				self.link = None;
+				self.inner.end_link ();
+			},
+		}
+	}
+
+}
+
+impl <'tar, 'dat, Inner: Target <'dat>> Target <'dat> for ClipTarget <'tar, 'dat, Inner> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.inner.width ()
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.line.push (Event::Attr (attr));
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		self.line.push (Event::Text (text));
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.flush_line ();
+		self.inner.newline ();
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		self.line.push (Event::BeginLink (url));
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+		self.line.push (Event::EndLink);
+	}
+
+}
+
+impl <'tar, 'dat, Inner: Target <'dat>> Drop for ClipTarget <'tar, 'dat, Inner> {
+
+	#[ inline ]
+	fn drop (& mut self) {
+		self.flush_line ();
+	}
+
+}
+
+pub struct Clipped <'dat, Inner: Printable <'dat>> {
+	inner: Inner,
+	overflow: Overflow,
+	ellipsis: Option <Cow <'dat, str>>,
+}
+
+impl <'dat, Inner: Printable <'dat>> Clipped <'dat, Inner> {
+
+	#[ inline ]
+	pub fn new (inner: Inner, overflow: Overflow) -> Self {
+		Self { inner, overflow, ellipsis: None }
+	}
+
+	#[ inline ]
+	pub fn with_ellipsis (mut self, ellipsis: impl Into <Cow <'dat, str>>) -> Self {
+		self.ellipsis = Some (ellipsis.into ());
+		self
+	}
+
+}
+
+impl <'dat, Inner: Printable <'dat>> Printable <'dat> for Clipped <'dat, Inner> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let mut clip_target = ClipTarget::new (target, self.overflow);
+		clip_target.ellipsis = self.ellipsis;
+		clip_target.push (self.inner);
+		clip_target.finish ();
+	}
+
+}
+
+fn slice_cow (text: Cow <'_, str>, end: usize) -> Cow <'_, str> {
+	match text {
+		Cow::Borrowed (text) => Cow::Borrowed (& text [ .. end]),
+		Cow::Owned (mut text) => {
+			text.truncate (end);
+			Cow::Owned (text)
+		},
+	}
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Colour;
+	use crate::grid::GridTarget;
+	use crate::tabs::SlimTabs;
+
+	#[ test ]
+	fn clip_ellipsis () {
+		let red = Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00));
+		let mut target = GridTarget::new (6);
+		let mut clip_target = ClipTarget::new (& mut target, Overflow::Clip).with_ellipsis ("…");
+		clip_target.push ("ab");
+		clip_target.push (red);
+		clip_target.push ("cd日本");
+		clip_target.push (Attr::default ());
+		clip_target.newline ();
+		clip_target.push ("short");
+		clip_target.finish ();
+		assert_eq! ("abcd…\nshort\n", target.to_text ());
+		assert_eq! ("..AAA\n.....\nA: fg=#ff0000\n", target.to_style_map ());
+	}
+
+	#[ test ]
+	fn clip_printable () {
+		let mut target = GridTarget::new (10);
+		target.push (Clipped::new (
+			SlimTabs::new (Attr::default (), vec! [
+				(Cow::Borrowed ("One"), Attr::default ()),
+				(Cow::Borrowed ("Two"), Attr::default ()),
+				(Cow::Borrowed ("Three"), Attr::default ()),
+			]),
+			Overflow::Clip));
+		assert_eq! ("  One   Tw\n", target.to_text ());
+	}
+
+	#[ test ]
+	fn wrap () {
+		let red = Attr::default ().background (Colour::new (0xff, 0x00, 0x00));
+		let mut target = GridTarget::new (4);
+		let mut clip_target = ClipTarget::new (& mut target, Overflow::Wrap);
+		clip_target.push ("ab");
+		clip_target.push (red);
+		clip_target.push ("cdef日本");
+		clip_target.push (Attr::default ());
+		clip_target.push ("g");
+		clip_target.newline ();
+		drop (clip_target);
+		assert_eq! ("abcd\nef日\n本g\n", target.to_text ());
+		assert_eq! ("..AA\nAAAA\nAA.\nA: bg=#ff0000\n", target.to_style_map ());
+	}
+
+}
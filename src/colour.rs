@@ -1,8 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
 use std::ops::Deref;
+use std::path::Path;
 use std::str::FromStr;
 
-#[ derive (Clone, Copy, Debug, Default, Eq, PartialEq) ]
+use crate::colour_names;
+
+#[ derive (Clone, Copy, Debug, Default, Eq, Hash, PartialEq) ]
 pub struct Colour {
 	pub red: u8,
 	pub green: u8,
@@ -16,6 +24,313 @@ impl Colour {
 		Self { red, green, blue }
 	}
 
+	pub fn from_ansi_256 (index: u8) -> Self {
+		match index {
+			0 ..= 15 => ANSI_16_PALETTE [index as usize],
+			16 ..= 231 => {
+				let index = index - 16;
+				Self::new (
+					CUBE_LEVELS [(index / 36) as usize],
+					CUBE_LEVELS [(index / 6 % 6) as usize],
+					CUBE_LEVELS [(index % 6) as usize])
+			},
+			_ => {
+				let level = 8 + (index - 232) * 10;
+				Self::new (level, level, level)
+			},
+		}
+	}
+
+	pub fn to_ansi_256 (self) -> u8 {
+		let cube_index = |value: u8| match value {
+			0 ..= 47 => 0,
+			48 ..= 114 => 1,
+			_ => (value - 35) / 40,
+		};
+		let (red_idx, green_idx, blue_idx) = (cube_index (self.red), cube_index (self.green), cube_index (self.blue));
+		let cube = Self::new (CUBE_LEVELS [red_idx as usize], CUBE_LEVELS [green_idx as usize], CUBE_LEVELS [blue_idx as usize]);
+		let average = (self.red as u32 + self.green as u32 + self.blue as u32) / 3;
+		let grey_idx = if average > 238 { 23 } else { average.saturating_sub (3) / 10 } as u8;
+		let grey_level = 8 + grey_idx * 10;
+		let grey = Self::new (grey_level, grey_level, grey_level);
+		if self.distance (grey) < self.distance (cube) {
+			232 + grey_idx
+		} else {
+			16 + red_idx * 36 + green_idx * 6 + blue_idx
+		}
+	}
+
+	pub fn to_ansi_16 (self) -> u8 {
+		ANSI_16_PALETTE.iter ()
+			.enumerate ()
+			.min_by_key (|& (_, & colour)| self.distance (colour))
+			.map (|(idx, _)| idx as u8)
+			.unwrap ()
+	}
+
+	pub fn mix (self, other: Self, amount: f64) -> Self {
+		let amount = amount.clamp (0.0, 1.0);
+		let [ red, green, blue ] = [ 0, 1, 2 ].map (|idx| {
+			let from = self.to_linear () [idx];
+			let to = other.to_linear () [idx];
+			from + (to - from) * amount
+		});
+		Self::from_linear ([ red, green, blue ])
+	}
+
+	pub fn mix_oklab (self, other: Self, amount: f64) -> Self {
+		let amount = amount.clamp (0.0, 1.0);
+		let (from, to) = (self.to_oklab (), other.to_oklab ());
+		Self::from_oklab ([ 0, 1, 2 ].map (|idx| from [idx] + (to [idx] - from [idx]) * amount))
+	}
+
+	#[ inline ]
+	pub fn lighten (self, amount: f64) -> Self {
+		self.adjust_lightness (amount)
+	}
+
+	#[ inline ]
+	pub fn darken (self, amount: f64) -> Self {
+		self.adjust_lightness (- amount)
+	}
+
+	pub fn relative_luminance (self) -> f64 {
+		let [ red, green, blue ] = self.to_linear ();
+		0.2126 * red + 0.7152 * green + 0.0722 * blue
+	}
+
+	pub fn contrast_ratio (self, other: Self) -> f64 {
+		let (first, second) = (self.relative_luminance (), other.relative_luminance ());
+		(first.max (second) + 0.05) / (first.min (second) + 0.05)
+	}
+
+	pub fn most_readable (self, candidates: impl IntoIterator <Item = Self>) -> Option <Self> {
+		candidates.into_iter ()
+			.map (|candidate| (candidate, self.contrast_ratio (candidate)))
+			.fold (None, |best: Option <(Self, f64)>, (candidate, ratio)| match best {
+				Some ((_, best_ratio)) if best_ratio >= ratio => best,
+				_ => Some ((candidate, ratio)),
+			})
+			.map (|(candidate, _)| candidate)
+	}
+
+	#[ inline ]
+	pub fn readable_foreground (self) -> Self {
+		self.most_readable ([ Self::new (0x00, 0x00, 0x00), Self::new (0xff, 0xff, 0xff) ]).unwrap ()
+	}
+
+	fn adjust_lightness (self, amount: f64) -> Self {
+		let [ lightness, a, b ] = self.to_oklab ();
+		Self::from_oklab ([ (lightness + amount).clamp (0.0, 1.0), a, b ])
+	}
+
+	fn to_linear (self) -> [f64; 3] {
+		[ self.red, self.green, self.blue ].map (|value| {
+			let value = value as f64 / 255.0;
+			if value <= 0.04045 { value / 12.92 } else { ((value + 0.055) / 1.055).powf (2.4) }
+		})
+	}
+
+	fn from_linear (linear: [f64; 3]) -> Self {
+		let [ red, green, blue ] = linear.map (|value| {
+			let value = value.clamp (0.0, 1.0);
+			let value = if value <= 0.0031308 { value * 12.92 } else { 1.055 * value.powf (1.0 / 2.4) - 0.055 };
+			(value * 255.0).round () as u8
+		});
+		Self::new (red, green, blue)
+	}
+
+	fn to_oklab (self) -> [f64; 3] {
+		let [ red, green, blue ] = self.to_linear ();
+		let long = (0.4122214708 * red + 0.5363325363 * green + 0.0514459929 * blue).cbrt ();
+		let medium = (0.2119034982 * red + 0.6806995451 * green + 0.1073969566 * blue).cbrt ();
+		let short = (0.0883024619 * red + 0.2817188376 * green + 0.6299787005 * blue).cbrt ();
+		[
+			0.2104542553 * long + 0.7936177850 * medium - 0.0040720468 * short,
+			1.9779984951 * long - 2.4285922050 * medium + 0.4505937099 * short,
+			0.0259040371 * long + 0.7827717662 * medium - 0.8086757660 * short,
+		]
+	}
+
+	fn from_oklab (oklab: [f64; 3]) -> Self {
+		let [ lightness, a, b ] = oklab;
+		let long = (lightness + 0.3963377774 * a + 0.2158037573 * b).powi (3);
+		let medium = (lightness - 0.1055613458 * a - 0.0638541728 * b).powi (3);
+		let short = (lightness - 0.0894841775 * a - 1.2914855480 * b).powi (3);
+		Self::from_linear ([
+			4.0767416621 * long - 3.3077115913 * medium + 0.2309699292 * short,
+			-1.2684380046 * long + 2.6097574011 * medium - 0.3413193965 * short,
+			-0.0041960863 * long - 0.7034186147 * medium + 1.7076147010 * short,
+		])
+	}
+
+	fn distance (self, other: Self) -> u32 {
+		let red = self.red.abs_diff (other.red) as u32;
+		let green = self.green.abs_diff (other.green) as u32;
+		let blue = self.blue.abs_diff (other.blue) as u32;
+		red * red * 2 + green * green * 4 + blue * blue * 3
+	}
+
+}
+
+const CUBE_LEVELS: [u8; 6] = [ 0x00, 0x5f, 0x87, 0xaf, 0xd7, 0xff ];
+
+pub const ANSI_16_PALETTE: [Colour; 16] = [
+	Colour::new (0x00, 0x00, 0x00),
+	Colour::new (0xcd, 0x00, 0x00),
+	Colour::new (0x00, 0xcd, 0x00),
+	Colour::new (0xcd, 0xcd, 0x00),
+	Colour::new (0x00, 0x00, 0xee),
+	Colour::new (0xcd, 0x00, 0xcd),
+	Colour::new (0x00, 0xcd, 0xcd),
+	Colour::new (0xe5, 0xe5, 0xe5),
+	Colour::new (0x7f, 0x7f, 0x7f),
+	Colour::new (0xff, 0x00, 0x00),
+	Colour::new (0x00, 0xff, 0x00),
+	Colour::new (0xff, 0xff, 0x00),
+	Colour::new (0x5c, 0x5c, 0xff),
+	Colour::new (0xff, 0x00, 0xff),
+	Colour::new (0x00, 0xff, 0xff),
+	Colour::new (0xff, 0xff, 0xff),
+];
+
+#[ derive (Clone, Copy, Debug, Eq, Hash, PartialEq) ]
+pub enum TermColour {
+	Ansi (AnsiColour),
+	Indexed (u8),
+	Rgb (Colour),
+}
+
+impl TermColour {
+
+	pub fn to_rgb (self) -> Colour {
+		match self {
+			Self::Ansi (ansi) => ANSI_16_PALETTE [ansi.index () as usize],
+			Self::Indexed (index) => Colour::from_ansi_256 (index),
+			Self::Rgb (colour) => colour,
+		}
+	}
+
+	pub fn to_ansi_256 (self) -> u8 {
+		match self {
+			Self::Ansi (ansi) => ansi.index (),
+			Self::Indexed (index) => index,
+			Self::Rgb (colour) => colour.to_ansi_256 (),
+		}
+	}
+
+	pub fn to_ansi_16 (self) -> AnsiColour {
+		match self {
+			Self::Ansi (ansi) => ansi,
+			Self::Indexed (index @ 0 ..= 15) => AnsiColour::from_index (index).unwrap (),
+			_ => AnsiColour::from_index (self.to_rgb ().to_ansi_16 ()).unwrap (),
+		}
+	}
+
+}
+
+impl From <Colour> for TermColour {
+
+	#[ inline ]
+	fn from (colour: Colour) -> Self {
+		Self::Rgb (colour)
+	}
+
+}
+
+impl From <AnsiColour> for TermColour {
+
+	#[ inline ]
+	fn from (ansi: AnsiColour) -> Self {
+		Self::Ansi (ansi)
+	}
+
+}
+
+impl fmt::Display for TermColour {
+
+	fn fmt (& self, formatter: & mut fmt::Formatter) -> fmt::Result {
+		match * self {
+			Self::Ansi (ansi) => write! (formatter, "{}", ansi.name ()),
+			Self::Indexed (index) => write! (formatter, "index-{index}"),
+			Self::Rgb (colour) => write! (formatter, "{colour}"),
+		}
+	}
+
+}
+
+#[ derive (Clone, Copy, Debug, Eq, Hash, PartialEq) ]
+pub enum AnsiColour {
+	Black,
+	Red,
+	Green,
+	Yellow,
+	Blue,
+	Magenta,
+	Cyan,
+	White,
+	BrightBlack,
+	BrightRed,
+	BrightGreen,
+	BrightYellow,
+	BrightBlue,
+	BrightMagenta,
+	BrightCyan,
+	BrightWhite,
+}
+
+impl AnsiColour {
+
+	pub const ALL: [Self; 16] = [
+		Self::Black,
+		Self::Red,
+		Self::Green,
+		Self::Yellow,
+		Self::Blue,
+		Self::Magenta,
+		Self::Cyan,
+		Self::White,
+		Self::BrightBlack,
+		Self::BrightRed,
+		Self::BrightGreen,
+		Self::BrightYellow,
+		Self::BrightBlue,
+		Self::BrightMagenta,
+		Self::BrightCyan,
+		Self::BrightWhite,
+	];
+
+	#[ inline ]
+	pub fn from_index (index: u8) -> Option <Self> {
+		Self::ALL.get (index as usize).copied ()
+	}
+
+	#[ inline ]
+	pub const fn index (self) -> u8 {
+		self as u8
+	}
+
+	pub const fn name (self) -> & 'static str {
+		match self {
+			Self::Black => "black",
+			Self::Red => "red",
+			Self::Green => "green",
+			Self::Yellow => "yellow",
+			Self::Blue => "blue",
+			Self::Magenta => "magenta",
+			Self::Cyan => "cyan",
+			Self::White => "white",
+			Self::BrightBlack => "bright-black",
+			Self::BrightRed => "bright-red",
+			Self::BrightGreen => "bright-green",
+			Self::BrightYellow => "bright-yellow",
+			Self::BrightBlue => "bright-blue",
+			Self::BrightMagenta => "bright-magenta",
+			Self::BrightCyan => "bright-cyan",
+			Self::BrightWhite => "bright-white",
+		}
+	}
+
 }
 
 impl FromStr for Colour {
@@ -23,86 +338,410 @@ impl FromStr for Colour {
 	type Err = ParseColourError;
 
 	fn from_str (source: & str) -> Result <Self, ParseColourError> {
-		let source_chars = source.chars ().count ();
-		if source_chars == 4 {
-			let first_char = source.chars ().next ().unwrap ();
-			if first_char != '#' { return Err (ParseColourError::InvalidFirstChar (first_char)) }
-			let value = u16::from_str_radix (& source [1 .. ], 16).map_err (|_| ParseColourError::ValueParse) ?;
-			return Ok (Self {
-				red: ((value & 0xf00) >> 8) as u8 * 0x11,
-				green: ((value & 0x0f0) >> 4) as u8 * 0x11,
-				blue: (value & 0x00f) as u8 * 0x11,
-			});
+		let AlphaColour { colour, alpha } = source.parse () ?;
+		if alpha != 0xff { return Err (ParseColourError::UnexpectedAlpha (alpha)) }
+		Ok (colour)
+	}
+
+}
+
+impl fmt::Display for Colour {
+
+	fn fmt (& self, formatter: & mut fmt::Formatter) -> fmt::Result {
+		let Self { red, green, blue } = * self;
+		write! (formatter, "#{red:02x}{green:02x}{blue:02x}")
+	}
+
+}
+
+#[ derive (Clone, Copy, Debug, Eq, Hash, PartialEq) ]
+pub struct AlphaColour {
+	pub colour: Colour,
+	pub alpha: u8,
+}
+
+impl AlphaColour {
+
+	#[ inline ]
+	pub const fn new (colour: Colour, alpha: u8) -> Self {
+		Self { colour, alpha }
+	}
+
+	pub fn over (self, background: Colour) -> Colour {
+		let alpha = self.alpha as u32;
+		let blend = |fg: u8, bg: u8| ((fg as u32 * alpha + bg as u32 * (255 - alpha) + 127) / 255) as u8;
+		Colour::new (
+			blend (self.colour.red, background.red),
+			blend (self.colour.green, background.green),
+			blend (self.colour.blue, background.blue))
+	}
+
+}
+
+impl From <Colour> for AlphaColour {
+
+	#[ inline ]
+	fn from (colour: Colour) -> Self {
+		Self::new (colour, 0xff)
+	}
+
+}
+
+impl FromStr for AlphaColour {
+
+	type Err = ParseColourError;
+
+	fn from_str (source: & str) -> Result <Self, ParseColourError> {
+		let source = source.trim ();
+		if let Some (hex) = source.strip_prefix ('#') {
+			return parse_hex (hex);
 		}
-		if source_chars == 7 {
-			let first_char = source.chars ().next ().unwrap ();
-			if first_char != '#' { return Err (ParseColourError::InvalidFirstChar (first_char)) }
-			let value = u32::from_str_radix (& source [1 .. ], 16).map_err (|_| ParseColourError::ValueParse) ?;
-			return Ok (Self {
-				red: ((value & 0xff0000) >> 16) as u8,
-				green: ((value & 0x00ff00) >> 8) as u8,
-				blue: (value & 0x0000ff) as u8,
-			});
+		if let Some ((function, args)) = source.split_once ('(') {
+			let args = args.strip_suffix (')').ok_or (ParseColourError::UnterminatedFunction) ?;
+			return parse_function (function.trim (), args);
 		}
-		Err (ParseColourError::InvalidLength (source_chars))
+		let Some (first_char) = source.chars ().next () else {
+			return Err (ParseColourError::InvalidLength (0));
+		};
+		if ! first_char.is_ascii_alphabetic () { return Err (ParseColourError::InvalidFirstChar (first_char)) }
+		colour_names::lookup (source)
+			.map (Self::from)
+			.ok_or_else (|| ParseColourError::UnknownName (source.to_owned ()))
+	}
+
+}
+
+impl fmt::Display for AlphaColour {
+
+	fn fmt (& self, formatter: & mut fmt::Formatter) -> fmt::Result {
+		write! (formatter, "{}", self.colour) ?;
+		if self.alpha != 0xff { write! (formatter, "{:02x}", self.alpha) ?; }
+		Ok (())
+	}
+
+}
+
+fn parse_hex (hex: & str) -> Result <AlphaColour, ParseColourError> {
+	let hex_chars = hex.chars ().count ();
+	if ! matches! (hex_chars, 3 | 4 | 6 | 8) { return Err (ParseColourError::InvalidLength (hex_chars + 1)) }
+	if ! hex.chars ().all (|ch| ch.is_ascii_hexdigit ()) { return Err (ParseColourError::ValueParse) }
+	let digits: Vec <u8> = if hex_chars <= 4 {
+		hex.chars ().map (|ch| ch.to_digit (16).unwrap () as u8 * 0x11).collect ()
+	} else {
+		(0 .. hex_chars / 2).map (|idx| u8::from_str_radix (& hex [idx * 2 .. idx * 2 + 2], 16).unwrap ()).collect ()
+	};
+	Ok (AlphaColour::new (
+		Colour::new (digits [0], digits [1], digits [2]),
+		digits.get (3).copied ().unwrap_or (0xff)))
+}
+
+fn parse_function (function: & str, args: & str) -> Result <AlphaColour, ParseColourError> {
+	let args: Vec <& str> = args.split (',').map (str::trim).collect ();
+	if args.len () != 3 && args.len () != 4 {
+		return Err (ParseColourError::ArgumentCount (function.to_owned (), args.len ()));
 	}
+	let alpha = args.get (3).map (|& arg| parse_alpha (arg)).transpose () ?.unwrap_or (0xff);
+	let colour = match function.to_ascii_lowercase ().as_str () {
+		"rgb" | "rgba" => Colour::new (
+			parse_channel (args [0]) ?,
+			parse_channel (args [1]) ?,
+			parse_channel (args [2]) ?),
+		"hsl" | "hsla" => {
+			let hue = args [0].strip_suffix ("deg").unwrap_or (args [0]);
+			let hue = parse_number (hue, args [0]) ?;
+			let saturation = parse_percent (args [1]) ?;
+			let lightness = parse_percent (args [2]) ?;
+			hsl_to_rgb (hue, saturation, lightness)
+		},
+		_ => return Err (ParseColourError::InvalidFunction (function.to_owned ())),
+	};
+	Ok (AlphaColour::new (colour, alpha))
+}
+
+fn parse_number (value: & str, arg: & str) -> Result <f64, ParseColourError> {
+	value.trim ().parse::<f64> ().ok ()
+		.filter (|value| value.is_finite ())
+		.ok_or_else (|| ParseColourError::InvalidArgument (arg.to_owned ()))
+}
+
+fn parse_percent (arg: & str) -> Result <f64, ParseColourError> {
+	let value = parse_number (arg.strip_suffix ('%').unwrap_or (arg), arg) ?;
+	if ! (0.0 ..= 100.0).contains (& value) { return Err (ParseColourError::InvalidArgument (arg.to_owned ())) }
+	Ok (value / 100.0)
+}
+
+fn parse_channel (arg: & str) -> Result <u8, ParseColourError> {
+	let value = if arg.ends_with ('%') {
+		parse_percent (arg) ? * 255.0
+	} else {
+		parse_number (arg, arg) ?
+	};
+	if ! (0.0 ..= 255.0).contains (& value) { return Err (ParseColourError::InvalidArgument (arg.to_owned ())) }
+	Ok (value.round () as u8)
+}
 
+fn parse_alpha (arg: & str) -> Result <u8, ParseColourError> {
+	let value = if arg.ends_with ('%') { parse_percent (arg) ? } else { parse_number (arg, arg) ? };
+	if ! (0.0 ..= 1.0).contains (& value) { return Err (ParseColourError::InvalidArgument (arg.to_owned ())) }
+	Ok ((value * 255.0).round () as u8)
+}
+
+fn hsl_to_rgb (hue: f64, saturation: f64, lightness: f64) -> Colour {
+	let hue = hue.rem_euclid (360.0) / 60.0;
+	let chroma = (1.0 - (2.0 * lightness - 1.0).abs ()) * saturation;
+	let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs ());
+	let (red, green, blue) = match hue as u32 {
+		0 => (chroma, second, 0.0),
+		1 => (second, chroma, 0.0),
+		2 => (0.0, chroma, second),
+		3 => (0.0, second, chroma),
+		4 => (second, 0.0, chroma),
+		_ => (chroma, 0.0, second),
+	};
+	let base = lightness - chroma / 2.0;
+	let channel = |value: f64| ((value + base) * 255.0).round ().clamp (0.0, 255.0) as u8;
+	Colour::new (channel (red), channel (green), channel (blue))
+}
+
+#[ inline ]
+pub fn is_literal (value: & str) -> bool {
+	value.starts_with ('#') || value.contains ('(')
 }
 
 #[ derive (Debug, Eq, PartialEq, thiserror::Error) ]
 pub enum ParseColourError {
-	#[ error ("invalid length {0} (should be 4 or 7)") ]
+	#[ error ("invalid length {0} (should be 4, 5, 7 or 9)") ]
 	InvalidLength (usize),
-	#[ error ("should start with '#', not '{0}'") ]
+	#[ error ("should start with '#' or a letter, not '{0}'") ]
 	InvalidFirstChar (char),
 	#[ error ("invalid hex value") ]
 	ValueParse,
+	#[ error ("unknown colour name '{0}'") ]
+	UnknownName (String),
+	#[ error ("unknown colour function '{0}'") ]
+	InvalidFunction (String),
+	#[ error ("missing ')' after function arguments") ]
+	UnterminatedFunction,
+	#[ error ("{0} takes 3 or 4 arguments, not {1}") ]
+	ArgumentCount (String, usize),
+	#[ error ("invalid argument '{0}'") ]
+	InvalidArgument (String),
+	#[ error ("unexpected alpha value {0} (colour must be opaque)") ]
+	UnexpectedAlpha (u8),
 }
 
 pub struct ColoursMap {
 	data: HashMap <String, Colour>,
+	layers: HashMap <String, String>,
 }
 
 impl ColoursMap {
 
+	pub fn load (path: impl AsRef <Path>) -> Result <Self, ColoursMapLoadError> {
+		let source = fs::read_to_string (path) ?;
+		Ok (Self::parse (& source) ?)
+	}
+
+	#[ inline ]
+	pub fn parse (source: & str) -> Result <Self, ColoursMapErrors> {
+		Self::layered ([ ColoursLayer::default ().parse (source) ])
+	}
+
 	pub fn build (
 		source: impl IntoIterator <Item = (impl AsRef <str>, impl AsRef <str>)>,
-	) -> Result <Self, ColoursMapBuildError> {
-		let mut remain: HashMap <String, String> = HashMap::new ();
+	) -> Result <Self, ColoursMapErrors> {
+		let layer = source.into_iter ()
+			.fold (ColoursLayer::default (), |layer, (name, value)| layer.entry (name, value));
+		Self::layered ([ layer ])
+	}
+
+	pub fn build_with_lines (
+		source: impl IntoIterator <Item = (usize, impl AsRef <str>, impl AsRef <str>)>,
+	) -> Result <Self, ColoursMapErrors> {
+		let layer = source.into_iter ()
+			.fold (ColoursLayer::default (), |layer, (line, name, value)| layer.entry_at (line, name, value));
+		Self::layered ([ layer ])
+	}
+
+	pub fn layered (layers: impl IntoIterator <Item = ColoursLayer>) -> Result <Self, ColoursMapErrors> {
+		let layers: Vec <ColoursLayer> = layers.into_iter ().collect ();
+		let mut errors = Vec::new ();
+		let mut source: Vec <(usize, & ColoursEntry)> = Vec::new ();
+		let mut positions: HashMap <& str, usize> = HashMap::new ();
+		for (layer_idx, layer) in layers.iter ().enumerate () {
+			for entry in & layer.entries {
+				match positions.get (entry.name.as_str ()) {
+					Some (& idx) => source [idx] = (layer_idx, entry),
+					None => {
+						positions.insert (& entry.name, source.len ());
+						source.push ((layer_idx, entry));
+					},
+				}
+			}
+			for & (line, ref line_text) in & layer.syntax_errors {
+				errors.push (((layer_idx, Some (line), usize::MAX), ColoursMapDiagnostic {
+					layer: layer.name.clone (),
+					line: Some (line),
+					error: ColoursMapBuildError::Syntax (line_text.to_owned ()),
+				}));
+			}
+		}
+		let diagnostic = |idx: usize, error| {
+			let (layer_idx, entry) = source [idx];
+			((layer_idx, entry.line, idx), ColoursMapDiagnostic {
+				layer: layers [layer_idx].name.clone (),
+				line: entry.line,
+				error,
+			})
+		};
+		let mut references: HashMap <& str, & str> = HashMap::new ();
 		let mut data: HashMap <String, Colour> = HashMap::new ();
-		for (name, value) in source {
-			let name = name.as_ref ();
-			let value = value.as_ref ();
-			if value.chars ().next () == Some ('#') {
-				data.insert (
-					name.to_owned (),
-					value.parse ()
-						.map_err (|err| ColoursMapBuildError::ParseError (name.to_owned (), err)) ?);
+		for (idx, & (_, entry)) in source.iter ().enumerate () {
+			let ColoursEntry { ref name, ref value, .. } = * entry;
+			if is_literal (value) {
+				match value.parse () {
+					Ok (colour) => { data.insert (name.to_owned (), colour); },
+					Err (err) => errors.push (diagnostic (idx, ColoursMapBuildError::ParseError (name.to_owned (), err))),
+				}
+			} else if positions.contains_key (value.as_str ()) {
+				references.insert (name, value);
+			} else if let Some (colour) = colour_names::lookup (value) {
+				data.insert (name.to_owned (), colour);
 			} else {
-				remain.insert (name.to_owned (), value.to_owned ());
+				errors.push (diagnostic (idx, ColoursMapBuildError::InvalidReference (name.to_owned (), value.to_owned ())));
 			}
 		}
-		for (name, value) in & remain {
-			if ! remain.contains_key (value) && ! data.contains_key (value) {
-				return Err (ColoursMapBuildError::InvalidReference (name.to_owned (), value.to_owned ()));
+		let mut reported: HashSet <& str> = HashSet::new ();
+		for & (_, entry) in & source {
+			let name = entry.name.as_str ();
+			if ! references.contains_key (name) || data.contains_key (name) { continue }
+			let mut path = vec! [ name ];
+			while let Some (& next) = references.get (path.last ().unwrap ()) {
+				if let Some (& colour) = data.get (next) {
+					for name in path { data.insert (name.to_owned (), colour); }
+					break;
+				}
+				if let Some (cycle_start) = path.iter ().position (|& name| name == next) {
+					let mut cycle = path.split_off (cycle_start);
+					if cycle.iter ().any (|name| reported.contains (name)) { break }
+					reported.extend (cycle.iter ().copied ());
+					let first = (0 .. cycle.len ()).min_by_key (|& idx| positions [cycle [idx]]).unwrap ();
+					cycle.rotate_left (first);
+					cycle.push (cycle [0]);
+					errors.push (diagnostic (
+						positions [cycle [0]],
+						ColoursMapBuildError::CircularReference (cycle.into_iter ().map (str::to_owned).collect ())));
+					break;
+				}
+				path.push (next);
 			}
 		}
-		while ! remain.is_empty () {
-			let mut progress = false;
-			remain.retain (|name, value| {
-				let Some (& colour) = data.get (value) else { return true };
-				data.insert (name.to_owned (), colour);
-				progress = true;
-				false
-			});
-			if ! progress {
-				let (name, value) = remain.iter ().next ().unwrap ();
-				return Err (ColoursMapBuildError::CircularReference (
-					name.clone (),
-					value.clone ()));
+		if ! errors.is_empty () {
+			errors.sort_by_key (|& (key, _)| key);
+			return Err (ColoursMapErrors { errors: errors.into_iter ().map (|(_, error)| error).collect () });
+		}
+		let layers = source.iter ()
+			.filter_map (|& (layer_idx, entry)| Some ((entry.name.clone (), layers [layer_idx].name.clone () ?)))
+			.collect ();
+		Ok (Self { data, layers })
+	}
+
+	#[ inline ]
+	pub fn layer (& self, name: & str) -> Option <& str> {
+		self.layers.get (name).map (String::as_str)
+	}
+
+}
+
+impl fmt::Display for ColoursMap {
+
+	fn fmt (& self, formatter: & mut fmt::Formatter) -> fmt::Result {
+		let mut names: Vec <& String> = self.data.keys ().collect ();
+		names.sort ();
+		for name in names {
+			writeln! (formatter, "{name} = {}", self.data [name]) ?;
+		}
+		Ok (())
+	}
+
+}
+
+#[ derive (Debug, Default) ]
+pub struct ColoursLayer {
+	name: Option <String>,
+	entries: Vec <ColoursEntry>,
+	syntax_errors: Vec <(usize, String)>,
+}
+
+#[ derive (Debug) ]
+struct ColoursEntry {
+	line: Option <usize>,
+	name: String,
+	value: String,
+}
+
+impl ColoursLayer {
+
+	#[ inline ]
+	pub fn new (name: impl Into <String>) -> Self {
+		Self { name: Some (name.into ()), .. Self::default () }
+	}
+
+	#[ inline ]
+	pub fn name (& self) -> Option <& str> {
+		self.name.as_deref ()
+	}
+
+	#[ inline ]
+	pub fn entry (self, name: impl AsRef <str>, value: impl AsRef <str>) -> Self {
+		self.push_entry (None, name.as_ref (), value.as_ref ())
+	}
+
+	#[ inline ]
+	pub fn entry_at (self, line: usize, name: impl AsRef <str>, value: impl AsRef <str>) -> Self {
+		self.push_entry (Some (line), name.as_ref (), value.as_ref ())
+	}
+
+	pub fn parse (mut self, source: & str) -> Self {
+		for (line_idx, line) in source.lines ().enumerate () {
+			let line_num = line_idx + 1;
+			let line = line.trim ();
+			if line.is_empty () || line.starts_with ('#') { continue }
+			match line.split_once ('=') {
+				Some ((name, value)) => self = self.push_entry (Some (line_num), name, value),
+				None => self.syntax_errors.push ((line_num, line.to_owned ())),
 			}
 		}
-		Ok (Self { data })
+		self
+	}
+
+	#[ inline ]
+	pub fn from_env (name: impl Into <String>, prefix: & str) -> Self {
+		Self::from_vars (name, prefix, env::vars ())
+	}
+
+	pub fn from_vars (
+		name: impl Into <String>,
+		prefix: & str,
+		vars: impl IntoIterator <Item = (impl AsRef <str>, impl AsRef <str>)>,
+	) -> Self {
+		let mut vars: Vec <(String, String)> = vars.into_iter ()
+			.filter_map (|(key, value)| {
+				let key = key.as_ref ().strip_prefix (prefix) ?;
+				Some ((key.to_ascii_lowercase ().replace ('_', "-"), value.as_ref ().to_owned ()))
+			})
+			.collect ();
+		vars.sort ();
+		vars.into_iter ().fold (Self::new (name), |layer, (name, value)| layer.entry (name, value))
+	}
+
+	fn push_entry (mut self, line: Option <usize>, name: & str, value: & str) -> Self {
+		self.entries.push (ColoursEntry {
+			line,
+			name: name.trim ().to_owned (),
+			value: value.trim ().to_owned (),
+		});
+		self
 	}
 
 }
@@ -118,14 +757,56 @@ impl Deref for ColoursMap {
 
 }
 
+#[ derive (Debug, Eq, PartialEq) ]
+pub struct ColoursMapErrors {
+	pub errors: Vec <ColoursMapDiagnostic>,
+}
+
+impl fmt::Display for ColoursMapErrors {
+
+	fn fmt (& self, formatter: & mut fmt::Formatter) -> fmt::Result {
+		for (idx, diagnostic) in self.errors.iter ().enumerate () {
+			if idx > 0 { writeln! (formatter) ? }
+			write! (formatter, "{diagnostic}") ?;
+		}
+		Ok (())
+	}
+
+}
+
+impl Error for ColoursMapErrors {}
+
+#[ derive (Debug, thiserror::Error) ]
+pub enum ColoursMapLoadError {
+	#[ error ("Error reading colours") ]
+	Io (#[ from ] io::Error),
+	#[ error ("{0}") ]
+	Colours (#[ from ] ColoursMapErrors),
+}
+
+#[ derive (Debug, Eq, PartialEq, thiserror::Error) ]
+#[ error (
+	"{}{}{error}",
+	.layer.as_ref ().map (|layer| format! ("{layer}: ")).unwrap_or_default (),
+	.line.map (|line| format! ("line {line}: ")).unwrap_or_default (),
+) ]
+pub struct ColoursMapDiagnostic {
+	pub layer: Option <String>,
+	pub line: Option <usize>,
+	#[ source ]
+	pub error: ColoursMapBuildError,
+}
+
 #[ derive (Debug, Eq, PartialEq, thiserror::Error) ]
 pub enum ColoursMapBuildError {
-	#[ error ("Parse error for {0}") ]
+	#[ error ("Syntax error: {0}") ]
+	Syntax (String),
+	#[ error ("Parse error for {0}: {1}") ]
 	ParseError (String, #[ source ] ParseColourError),
 	#[ error ("Invalid colour name for {0}: {1}") ]
 	InvalidReference (String, String),
-	#[ error ("Circular reference for {0}: {1}") ]
-	CircularReference (String, String),
+	#[ error ("Circular reference: {}", .0.join (" → ")) ]
+	CircularReference (Vec <String>),
 }
 
 #[ cfg (test) ]
@@ -140,13 +821,105 @@ mod tests {
 		assert_eq! (Ok (Colour::new (0xdd, 0xaa, 0x99)), Colour::from_str ("#da9"));
 		assert_eq! (Ok (Colour::new (0xcc, 0x44, 0x22)), Colour::from_str ("#C42"));
 		assert_eq! (Err (ParseColourError::InvalidLength (0)), Colour::from_str (""));
-		assert_eq! (Err (ParseColourError::InvalidLength (5)), Colour::from_str ("#abcd"));
-		assert_eq! (Err (ParseColourError::InvalidFirstChar ('w')), Colour::from_str ("wxyz"));
+		assert_eq! (Err (ParseColourError::InvalidLength (6)), Colour::from_str ("#abcde"));
+		assert_eq! (Err (ParseColourError::UnexpectedAlpha (0xdd)), Colour::from_str ("#abcd"));
+		assert_eq! (Err (ParseColourError::InvalidFirstChar ('1')), Colour::from_str ("123"));
+		assert_eq! (Err (ParseColourError::UnknownName ("wxyz".to_owned ())), Colour::from_str ("wxyz"));
 		assert_eq! (Err (ParseColourError::ValueParse), Colour::from_str ("#01g"));
+		assert_eq! (Ok (Colour::new (0x66, 0x33, 0x99)), Colour::from_str ("RebeccaPurple"));
+		assert_eq! (Ok (Colour::new (0x12, 0x34, 0x56)), Colour::from_str ("#123456ff"));
+		assert_eq! (Ok (Colour::new (10, 20, 255)), Colour::from_str ("rgb(10, 20, 100%)"));
+		assert_eq! (Ok (Colour::new (0x00, 0x80, 0x00)), Colour::from_str ("hsl(120deg, 100%, 25%)"));
+		assert_eq! (Ok (Colour::new (0xff, 0xff, 0xff)), Colour::from_str ("hsl(0, 0%, 100%)"));
+		assert_eq! (Ok (Colour::new (0xcc, 0x33, 0x99)), Colour::from_str ("hsl(-40, 60%, 50%)"));
+		assert_eq! (Err (ParseColourError::UnterminatedFunction), Colour::from_str ("rgb(1, 2, 3"));
+		assert_eq! (Err (ParseColourError::InvalidFunction ("cmyk".to_owned ())), Colour::from_str ("cmyk(1, 2, 3)"));
+		assert_eq! (Err (ParseColourError::ArgumentCount ("rgb".to_owned (), 2)), Colour::from_str ("rgb(1, 2)"));
+		assert_eq! (Err (ParseColourError::InvalidArgument ("256".to_owned ())), Colour::from_str ("rgb(1, 2, 256)"));
+		assert_eq! (Err (ParseColourError::InvalidArgument ("x%".to_owned ())), Colour::from_str ("hsl(0, x%, 50%)"));
+		assert_eq! (Err (ParseColourError::UnexpectedAlpha (128)), Colour::from_str ("rgba(1, 2, 3, 50%)"));
 	}
 
 	#[ test ]
-	fn colours_map_build_ok () -> anyhow::Result <(), ColoursMapBuildError> {
+	fn alpha_colour () -> anyhow::Result <()> {
+		let colour: AlphaColour = "#ff000080".parse () ?;
+		assert_eq! (AlphaColour::new (Colour::new (0xff, 0x00, 0x00), 0x80), colour);
+		assert_eq! (Colour::new (0x80, 0x00, 0x7f), colour.over (Colour::new (0x00, 0x00, 0xff)));
+		assert_eq! (colour, "rgba(255, 0, 0, 0.5)".parse () ?);
+		assert_eq! ("#ff000080", colour.to_string ());
+		assert_eq! ("#ff0000", AlphaColour::from (colour.colour).to_string ());
+		Ok (())
+	}
+
+	#[ test ]
+	fn colour_display () {
+		for source in [ "#000000", "#429d4a", "#ffffff" ] {
+			assert_eq! (source, Colour::from_str (source).unwrap ().to_string ());
+		}
+		assert_eq! ("#ccddee", Colour::from_str ("#CDE").unwrap ().to_string ());
+	}
+
+	#[ test ]
+	fn colour_to_ansi () {
+		assert_eq! (16, Colour::new (0x00, 0x00, 0x00).to_ansi_256 ());
+		assert_eq! (231, Colour::new (0xff, 0xff, 0xff).to_ansi_256 ());
+		assert_eq! (196, Colour::new (0xff, 0x00, 0x00).to_ansi_256 ());
+		assert_eq! (244, Colour::new (0x80, 0x80, 0x80).to_ansi_256 ());
+		assert_eq! (66, Colour::new (0x50, 0x80, 0x80).to_ansi_256 ());
+		assert_eq! (0, Colour::new (0x10, 0x10, 0x10).to_ansi_16 ());
+		assert_eq! (9, Colour::new (0xf0, 0x20, 0x20).to_ansi_16 ());
+		assert_eq! (4, Colour::new (0x00, 0x00, 0xc0).to_ansi_16 ());
+		assert_eq! (15, Colour::new (0xf8, 0xf8, 0xf8).to_ansi_16 ());
+	}
+
+	#[ test ]
+	fn term_colour () {
+		assert_eq! (Colour::new (0xff, 0x00, 0x00), Colour::from_ansi_256 (196));
+		assert_eq! (Colour::new (0x80, 0x80, 0x80), Colour::from_ansi_256 (244));
+		for index in 16 ..= 255 {
+			assert_eq! (index, Colour::from_ansi_256 (index).to_ansi_256 ());
+		}
+		assert_eq! (AnsiColour::BrightRed, TermColour::Indexed (9).to_ansi_16 ());
+		assert_eq! (AnsiColour::Blue, TermColour::Rgb (Colour::new (0x00, 0x00, 0xc0)).to_ansi_16 ());
+		assert_eq! (AnsiColour::BrightWhite, TermColour::Indexed (231).to_ansi_16 ());
+		assert_eq! (3, TermColour::Ansi (AnsiColour::Yellow).to_ansi_256 ());
+		assert_eq! ("bright-cyan", TermColour::Ansi (AnsiColour::BrightCyan).to_string ());
+		assert_eq! ("index-42", TermColour::Indexed (42).to_string ());
+		assert_eq! ("#010203", TermColour::from (Colour::new (1, 2, 3)).to_string ());
+	}
+
+	#[ test ]
+	fn colour_maths () {
+		let black = Colour::new (0x00, 0x00, 0x00);
+		let white = Colour::new (0xff, 0xff, 0xff);
+		let navy = Colour::new (0x00, 0x00, 0x80);
+		let yellow = Colour::new (0xff, 0xff, 0x00);
+		assert_eq! (Colour::new (0xbc, 0xbc, 0xbc), black.mix (white, 0.5));
+		assert_eq! (navy, navy.mix (yellow, 0.0));
+		assert_eq! (yellow, navy.mix (yellow, 1.0));
+		assert_eq! (navy, navy.mix_oklab (yellow, 0.0));
+		assert_eq! (yellow, navy.mix_oklab (yellow, 1.0));
+		assert_eq! (Colour::new (0x63, 0x63, 0x63), black.mix_oklab (white, 0.5));
+		for colour in [ black, white, navy, yellow, Colour::new (0x42, 0x9d, 0x4a) ] {
+			assert_eq! (colour, colour.lighten (0.0));
+		}
+		let grey = Colour::new (0x80, 0x80, 0x80);
+		assert_eq! (white, grey.lighten (1.0));
+		assert_eq! (black, grey.darken (1.0));
+		assert! (grey.lighten (0.1).relative_luminance () > grey.relative_luminance ());
+		assert! (grey.darken (0.1).relative_luminance () < grey.relative_luminance ());
+		assert! (black.relative_luminance ().abs () < 1e-9);
+		assert! ((white.relative_luminance () - 1.0).abs () < 1e-9);
+		assert! ((black.contrast_ratio (white) - 21.0).abs () < 1e-9);
+		assert! ((navy.contrast_ratio (navy) - 1.0).abs () < 1e-9);
+		assert_eq! (white, navy.readable_foreground ());
+		assert_eq! (black, yellow.readable_foreground ());
+		assert_eq! (Some (yellow), navy.most_readable ([ grey, yellow, black ]));
+		assert_eq! (None, navy.most_readable ([]));
+	}
+
+	#[ test ]
+	fn colours_map_build_ok () -> anyhow::Result <(), ColoursMapErrors> {
 		let map = ColoursMap::build ([
 			("black", "#000000"),
 			("white", "#ffffff"),
@@ -154,30 +927,120 @@ mod tests {
 			("default-background", "black"),
 			("inverse-foreground", "default-background"),
 			("inverse-background", "default-foreground"),
+			("accent", "rgb(255, 128, 0)"),
+			("error", "crimson"),
+			("warning", "accent"),
 		]) ?;
-		assert_eq! (6, map.len ());
+		assert_eq! (9, map.len ());
 		assert_eq! (Some (& Colour::new (0x00, 0x00, 0x00)), map.get ("black"));
 		assert_eq! (Some (& Colour::new (0xff, 0xff, 0xff)), map.get ("white"));
 		assert_eq! (Some (& Colour::new (0xff, 0xff, 0xff)), map.get ("default-foreground"));
 		assert_eq! (Some (& Colour::new (0x00, 0x00, 0x00)), map.get ("default-background"));
 		assert_eq! (Some (& Colour::new (0x00, 0x00, 0x00)), map.get ("inverse-foreground"));
 		assert_eq! (Some (& Colour::new (0xff, 0xff, 0xff)), map.get ("inverse-background"));
+		assert_eq! (Some (& Colour::new (0xff, 0x80, 0x00)), map.get ("warning"));
+		assert_eq! (Some (& Colour::new (0xdc, 0x14, 0x3c)), map.get ("error"));
 		Ok (())
 	}
 
 	#[ test ]
 	fn colours_map_build_error () {
+		let errors = |source: & [(& str, & str)]| -> Vec <ColoursMapBuildError> {
+			ColoursMap::build (source.iter ().copied ()).err ().unwrap ().errors.into_iter ()
+				.map (|diagnostic| { assert_eq! (None, diagnostic.line); diagnostic.error })
+				.collect ()
+		};
+		let cycle = |names: & [& str]| ColoursMapBuildError::CircularReference (
+			names.iter ().map (|& name| name.to_owned ()).collect ());
+		assert_eq! (
+			vec! [ ColoursMapBuildError::ParseError ("a".to_owned (), Colour::from_str ("#hello!").unwrap_err ()) ],
+			errors (& [ ("a", "#hello!") ]));
 		assert_eq! (
-			Some (ColoursMapBuildError::ParseError (
-				"a".to_owned (),
-				Colour::from_str ("#hello!").unwrap_err ())),
-			ColoursMap::build ([ ("a", "#hello!") ]).err ());
+			vec! [ ColoursMapBuildError::ParseError ("a".to_owned (), ParseColourError::ArgumentCount ("rgb".to_owned (), 1)) ],
+			errors (& [ ("a", "rgb(1)") ]));
 		assert_eq! (
-			Some (ColoursMapBuildError::InvalidReference ("a".to_owned (), "b".to_owned ())),
-			ColoursMap::build ([ ("a", "b") ]).err ());
+			vec! [ ColoursMapBuildError::InvalidReference ("a".to_owned (), "b".to_owned ()) ],
+			errors (& [ ("a", "b") ]));
+		assert_eq! (vec! [ cycle (& [ "a", "a" ]) ], errors (& [ ("a", "a") ]));
 		assert_eq! (
-			Some (ColoursMapBuildError::CircularReference ("a".to_owned (), "a".to_owned ())),
-			ColoursMap::build ([ ("a", "a") ]).err ());
+			vec! [
+				ColoursMapBuildError::InvalidReference ("a".to_owned (), "nope".to_owned ()),
+				cycle (& [ "b", "d", "c", "b" ]),
+				ColoursMapBuildError::ParseError ("e".to_owned (), ParseColourError::ValueParse),
+				cycle (& [ "f", "f" ]),
+			],
+			errors (& [
+				("x", "c"),
+				("a", "nope"),
+				("b", "d"),
+				("c", "b"),
+				("d", "c"),
+				("e", "#xyz"),
+				("ok", "#fff"),
+				("f", "f"),
+				("g", "a"),
+			]));
+	}
+
+	#[ test ]
+	fn colours_map_layered () -> anyhow::Result <()> {
+		let base = ColoursLayer::new ("base").parse ("\
+			black = #000000
+			white = #ffffff
+			accent = #0060c0
+			focus = accent
+			text = white
+		");
+		let user = ColoursLayer::new ("user")
+			.entry ("accent", "#c06000")
+			.entry ("muted", "grey");
+		let env = ColoursLayer::from_vars ("env", "JP_TERM_COLOUR_", [
+			("JP_TERM_COLOUR_TEXT", "black"),
+			("HOME", "/root"),
+		]);
+		let map = ColoursMap::layered ([ base, user, env ]) ?;
+		assert_eq! (Some (& Colour::new (0xc0, 0x60, 0x00)), map.get ("focus"));
+		assert_eq! (Some (& Colour::new (0x00, 0x00, 0x00)), map.get ("text"));
+		assert_eq! (Some ("base"), map.layer ("focus"));
+		assert_eq! (Some ("user"), map.layer ("accent"));
+		assert_eq! (Some ("env"), map.layer ("text"));
+		assert_eq! (None, map.layer ("missing"));
+		let text = map.to_string ();
+		assert_eq! (
+			"accent = #c06000\nblack = #000000\nfocus = #c06000\nmuted = #808080\ntext = #000000\nwhite = #ffffff\n",
+			text);
+		assert_eq! (map.data, ColoursMap::parse (& text) ?.data);
+		let errors = ColoursMap::layered ([
+			ColoursLayer::new ("base").parse ("a = #fff\nb = a"),
+			ColoursLayer::new ("user").parse ("a = b\noops"),
+		]).err ().unwrap ();
+		assert_eq! (
+			"user: line 1: Circular reference: a → b → a\nuser: line 2: Syntax error: oops",
+			errors.to_string ());
+		Ok (())
+	}
+
+	#[ test ]
+	fn colours_map_load () {
+		assert! (matches! (
+			ColoursMap::load ("/nonexistent/colours.ini"),
+			Err (ColoursMapLoadError::Io (err)) if err.kind () == io::ErrorKind::NotFound));
+	}
+
+	#[ test ]
+	fn colours_map_parse () -> anyhow::Result <()> {
+		let map = ColoursMap::parse ("# comment\nred = #f00\n\nerror = red\n") ?;
+		assert_eq! (Some (& Colour::new (0xff, 0x00, 0x00)), map.get ("error"));
+		let errors = ColoursMap::parse ("a = b\nbad line\nc = d\nd = c\n").err ().unwrap ();
+		assert_eq! (
+			vec! [ Some (1), Some (2), Some (3) ],
+			errors.errors.iter ().map (|diagnostic| diagnostic.line).collect::<Vec <_>> ());
+		assert_eq! (
+			"line 1: Invalid colour name for a: b\n\
+				line 2: Syntax error: bad line\n\
+				line 3: Circular reference: c → d → c",
+			errors.to_string ());
+		Ok (())
 	}
 
 }
@@ -0,0 +1,159 @@
+use crate::Colour;
+
+pub fn lookup (name: & str) -> Option <Colour> {
+	let name = name.to_ascii_lowercase ();
+	COLOUR_NAMES.binary_search_by_key (& name.as_str (), |& (name, _)| name)
+		.ok ()
+		.map (|idx| COLOUR_NAMES [idx].1)
+}
+
+pub const COLOUR_NAMES: [(& str, Colour); 148] = [
+	("aliceblue", Colour::new (0xf0, 0xf8, 0xff)),
+	("antiquewhite", Colour::new (0xfa, 0xeb, 0xd7)),
+	("aqua", Colour::new (0x00, 0xff, 0xff)),
+	("aquamarine", Colour::new (0x7f, 0xff, 0xd4)),
+	("azure", Colour::new (0xf0, 0xff, 0xff)),
+	("beige", Colour::new (0xf5, 0xf5, 0xdc)),
+	("bisque", Colour::new (0xff, 0xe4, 0xc4)),
+	("black", Colour::new (0x00, 0x00, 0x00)),
+	("blanchedalmond", Colour::new (0xff, 0xeb, 0xcd)),
+	("blue", Colour::new (0x00, 0x00, 0xff)),
+	("blueviolet", Colour::new (0x8a, 0x2b, 0xe2)),
+	("brown", Colour::new (0xa5, 0x2a, 0x2a)),
+	("burlywood", Colour::new (0xde, 0xb8, 0x87)),
+	("cadetblue", Colour::new (0x5f, 0x9e, 0xa0)),
+	("chartreuse", Colour::new (0x7f, 0xff, 0x00)),
+	("chocolate", Colour::new (0xd2, 0x69, 0x1e)),
+	("coral", Colour::new (0xff, 0x7f, 0x50)),
+	("cornflowerblue", Colour::new (0x64, 0x95, 0xed)),
+	("cornsilk", Colour::new (0xff, 0xf8, 0xdc)),
+	("crimson", Colour::new (0xdc, 0x14, 0x3c)),
+	("cyan", Colour::new (0x00, 0xff, 0xff)),
+	("darkblue", Colour::new (0x00, 0x00, 0x8b)),
+	("darkcyan", Colour::new (0x00, 0x8b, 0x8b)),
+	("darkgoldenrod", Colour::new (0xb8, 0x86, 0x0b)),
+	("darkgray", Colour::new (0xa9, 0xa9, 0xa9)),
+	("darkgreen", Colour::new (0x00, 0x64, 0x00)),
+	("darkgrey", Colour::new (0xa9, 0xa9, 0xa9)),
+	("darkkhaki", Colour::new (0xbd, 0xb7, 0x6b)),
+	("darkmagenta", Colour::new (0x8b, 0x00, 0x8b)),
+	("darkolivegreen", Colour::new (0x55, 0x6b, 0x2f)),
+	("darkorange", Colour::new (0xff, 0x8c, 0x00)),
+	("darkorchid", Colour::new (0x99, 0x32, 0xcc)),
+	("darkred", Colour::new (0x8b, 0x00, 0x00)),
+	("darksalmon", Colour::new (0xe9, 0x96, 0x7a)),
+	("darkseagreen", Colour::new (0x8f, 0xbc, 0x8f)),
+	("darkslateblue", Colour::new (0x48, 0x3d, 0x8b)),
+	("darkslategray", Colour::new (0x2f, 0x4f, 0x4f)),
+	("darkslategrey", Colour::new (0x2f, 0x4f, 0x4f)),
+	("darkturquoise", Colour::new (0x00, 0xce, 0xd1)),
+	("darkviolet", Colour::new (0x94, 0x00, 0xd3)),
+	("deeppink", Colour::new (0xff, 0x14, 0x93)),
+	("deepskyblue", Colour::new (0x00, 0xbf, 0xff)),
+	("dimgray", Colour::new (0x69, 0x69, 0x69)),
+	("dimgrey", Colour::new (0x69, 0x69, 0x69)),
+	("dodgerblue", Colour::new (0x1e, 0x90, 0xff)),
+	("firebrick", Colour::new (0xb2, 0x22, 0x22)),
+	("floralwhite", Colour::new (0xff, 0xfa, 0xf0)),
+	("forestgreen", Colour::new (0x22, 0x8b, 0x22)),
+	("fuchsia", Colour::new (0xff, 0x00, 0xff)),
+	("gainsboro", Colour::new (0xdc, 0xdc, 0xdc)),
+	("ghostwhite", Colour::new (0xf8, 0xf8, 0xff)),
+	("gold", Colour::new (0xff, 0xd7, 0x00)),
+	("goldenrod", Colour::new (0xda, 0xa5, 0x20)),
+	("gray", Colour::new (0x80, 0x80, 0x80)),
+	("green", Colour::new (0x00, 0x80, 0x00)),
+	("greenyellow", Colour::new (0xad, 0xff, 0x2f)),
+	("grey", Colour::new (0x80, 0x80, 0x80)),
+	("honeydew", Colour::new (0xf0, 0xff, 0xf0)),
+	("hotpink", Colour::new (0xff, 0x69, 0xb4)),
+	("indianred", Colour::new (0xcd, 0x5c, 0x5c)),
+	("indigo", Colour::new (0x4b, 0x00, 0x82)),
+	("ivory", Colour::new (0xff, 0xff, 0xf0)),
+	("khaki", Colour::new (0xf0, 0xe6, 0x8c)),
+	("lavender", Colour::new (0xe6, 0xe6, 0xfa)),
+	("lavenderblush", Colour::new (0xff, 0xf0, 0xf5)),
+	("lawngreen", Colour::new (0x7c, 0xfc, 0x00)),
+	("lemonchiffon", Colour::new (0xff, 0xfa, 0xcd)),
+	("lightblue", Colour::new (0xad, 0xd8, 0xe6)),
+	("lightcoral", Colour::new (0xf0, 0x80, 0x80)),
+	("lightcyan", Colour::new (0xe0, 0xff, 0xff)),
+	("lightgoldenrodyellow", Colour::new (0xfa, 0xfa, 0xd2)),
+	("lightgray", Colour::new (0xd3, 0xd3, 0xd3)),
+	("lightgreen", Colour::new (0x90, 0xee, 0x90)),
+	("lightgrey", Colour::new (0xd3, 0xd3, 0xd3)),
+	("lightpink", Colour::new (0xff, 0xb6, 0xc1)),
+	("lightsalmon", Colour::new (0xff, 0xa0, 0x7a)),
+	("lightseagreen", Colour::new (0x20, 0xb2, 0xaa)),
+	("lightskyblue", Colour::new (0x87, 0xce, 0xfa)),
+	("lightslategray", Colour::new (0x77, 0x88, 0x99)),
+	("lightslategrey", Colour::new (0x77, 0x88, 0x99)),
+	("lightsteelblue", Colour::new (0xb0, 0xc4, 0xde)),
+	("lightyellow", Colour::new (0xff, 0xff, 0xe0)),
+	("lime", Colour::new (0x00, 0xff, 0x00)),
+	("limegreen", Colour::new (0x32, 0xcd, 0x32)),
+	("linen", Colour::new (0xfa, 0xf0, 0xe6)),
+	("magenta", Colour::new (0xff, 0x00, 0xff)),
+	("maroon", Colour::new (0x80, 0x00, 0x00)),
+	("mediumaquamarine", Colour::new (0x66, 0xcd, 0xaa)),
+	("mediumblue", Colour::new (0x00, 0x00, 0xcd)),
+	("mediumorchid", Colour::new (0xba, 0x55, 0xd3)),
+	("mediumpurple", Colour::new (0x93, 0x70, 0xdb)),
+	("mediumseagreen", Colour::new (0x3c, 0xb3, 0x71)),
+	("mediumslateblue", Colour::new (0x7b, 0x68, 0xee)),
+	("mediumspringgreen", Colour::new (0x00, 0xfa, 0x9a)),
+	("mediumturquoise", Colour::new (0x48, 0xd1, 0xcc)),
+	("mediumvioletred", Colour::new (0xc7, 0x15, 0x85)),
+	("midnightblue", Colour::new (0x19, 0x19, 0x70)),
+	("mintcream", Colour::new (0xf5, 0xff, 0xfa)),
+	("mistyrose", Colour::new (0xff, 0xe4, 0xe1)),
+	("moccasin", Colour::new (0xff, 0xe4, 0xb5)),
+	("navajowhite", Colour::new (0xff, 0xde, 0xad)),
+	("navy", Colour::new (0x00, 0x00, 0x80)),
+	("oldlace", Colour::new (0xfd, 0xf5, 0xe6)),
+	("olive", Colour::new (0x80, 0x80, 0x00)),
+	("olivedrab", Colour::new (0x6b, 0x8e, 0x23)),
+	("orange", Colour::new (0xff, 0xa5, 0x00)),
+	("orangered", Colour::new (0xff, 0x45, 0x00)),
+	("orchid", Colour::new (0xda, 0x70, 0xd6)),
+	("palegoldenrod", Colour::new (0xee, 0xe8, 0xaa)),
+	("palegreen", Colour::new (0x98, 0xfb, 0x98)),
+	("paleturquoise", Colour::new (0xaf, 0xee, 0xee)),
+	("palevioletred", Colour::new (0xdb, 0x70, 0x93)),
+	("papayawhip", Colour::new (0xff, 0xef, 0xd5)),
+	("peachpuff", Colour::new (0xff, 0xda, 0xb9)),
+	("peru", Colour::new (0xcd, 0x85, 0x3f)),
+	("pink", Colour::new (0xff, 0xc0, 0xcb)),
+	("plum", Colour::new (0xdd, 0xa0, 0xdd)),
+	("powderblue", Colour::new (0xb0, 0xe0, 0xe6)),
+	("purple", Colour::new (0x80, 0x00, 0x80)),
+	("rebeccapurple", Colour::new (0x66, 0x33, 0x99)),
+	("red", Colour::new (0xff, 0x00, 0x00)),
+	("rosybrown", Colour::new (0xbc, 0x8f, 0x8f)),
+	("royalblue", Colour::new (0x41, 0x69, 0xe1)),
+	("saddlebrown", Colour::new (0x8b, 0x45, 0x13)),
+	("salmon", Colour::new (0xfa, 0x80, 0x72)),
+	("sandybrown", Colour::new (0xf4, 0xa4, 0x60)),
+	("seagreen", Colour::new (0x2e, 0x8b, 0x57)),
+	("seashell", Colour::new (0xff, 0xf5, 0xee)),
+	("sienna", Colour::new (0xa0, 0x52, 0x2d)),
+	("silver", Colour::new (0xc0, 0xc0, 0xc0)),
+	("skyblue", Colour::new (0x87, 0xce, 0xeb)),
+	("slateblue", Colour::new (0x6a, 0x5a, 0xcd)),
+	("slategray", Colour::new (0x70, 0x80, 0x90)),
+	("slategrey", Colour::new (0x70, 0x80, 0x90)),
+	("snow", Colour::new (0xff, 0xfa, 0xfa)),
+	("springgreen", Colour::new (0x00, 0xff, 0x7f)),
+	("steelblue", Colour::new (0x46, 0x82, 0xb4)),
+	("tan", Colour::new (0xd2, 0xb4, 0x8c)),
+	("teal", Colour::new (0x00, 0x80, 0x80)),
+	("thistle", Colour::new (0xd8, 0xbf, 0xd8)),
+	("tomato", Colour::new (0xff, 0x63, 0x47)),
+	("turquoise", Colour::new (0x40, 0xe0, 0xd0)),
+	("violet", Colour::new (0xee, 0x82, 0xee)),
+	("wheat", Colour::new (0xf5, 0xde, 0xb3)),
+	("white", Colour::new (0xff, 0xff, 0xff)),
+	("whitesmoke", Colour::new (0xf5, 0xf5, 0xf5)),
+	("yellow", Colour::new (0xff, 0xff, 0x00)),
+	("yellowgreen", Colour::new (0x9a, 0xcd, 0x32)),
+];
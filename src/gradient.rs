@@ -0,0 +1,191 @@
+use std::borrow::Cow;
+use std::iter;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{ Attr, Colour, Printable, Target };
+use crate::border_box::BorderType;
+
+#[ derive (Clone, Debug, Eq, PartialEq) ]
+pub struct Gradient {
+	stops: Vec <Colour>,
+}
+
+impl Gradient {
+
+	#[ inline ]
+	pub fn new (first: Colour, rest: impl IntoIterator <Item = Colour>) -> Self {
+		let stops = iter::once (first).chain (rest).collect ();
+		Self { stops }
+	}
+
+	#[ inline ]
+	pub fn stops (& self) -> & [Colour] {
+		& self.stops
+	}
+
+	pub fn at (& self, position: f64) -> Colour {
+		let segments = self.stops.len () - 1;
+		if segments == 0 { return self.stops [0] }
+		let scaled = position.clamp (0.0, 1.0) * segments as f64;
+		let segment = (scaled.floor () as usize).min (segments - 1);
+		self.stops [segment].mix_oklab (self.stops [segment + 1], scaled - segment as f64)
+	}
+
+	pub fn sample (& self, count: usize) -> Vec <Colour> {
+		(0 .. count)
+			.map (|idx| self.at (if count > 1 { idx as f64 / (count - 1) as f64 } else { 0.0 }))
+			.collect ()
+	}
+
+}
+
+pub struct GradientText <'dat> {
+	text: Cow <'dat, str>,
+	gradient: Gradient,
+	attr: Attr,
+}
+
+impl <'dat> GradientText <'dat> {
+
+	#[ inline ]
+	pub fn new (text: impl Into <Cow <'dat, str>>, gradient: Gradient) -> Self {
+		Self { text: text.into (), gradient, attr: Attr::default () }
+	}
+
+	#[ inline ]
+	pub fn attr (mut self, attr: Attr) -> Self {
+		self.attr = attr;
+		self
+	}
+
+}
+
+impl <'dat> Printable <'dat> for GradientText <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let Self { text, gradient, attr } = self;
+		let count = text.graphemes (true).count ();
+		let colours = gradient.sample (count);
+		match text {
+			Cow::Borrowed (text) => {
+				for (grapheme, colour) in text.graphemes (true).zip (colours) {
+					target.push (attr.foreground (colour));
+					target.push (grapheme);
+				}
+			},
+			Cow::Owned (text) => {
+				for (grapheme, colour) in text.graphemes (true).zip (colours) {
+					target.push (attr.foreground (colour));
+					target.push (grapheme.to_owned ());
+				}
+			},
+		}
+		target.push (Attr::default ());
+	}
+
+}
+
+pub struct GradientBorder {
+	type_: BorderType,
+	gradient: Gradient,
+	foreground: Colour,
+	width: usize,
+}
+
+impl GradientBorder {
+
+	#[ inline ]
+	pub fn new (type_: BorderType, gradient: Gradient, foreground: Colour, width: usize) -> Self {
+		Self { type_, gradient, foreground, width }
+	}
+
+	#[ inline ]
+	pub fn top (gradient: Gradient, foreground: Colour, width: usize) -> Self {
+		Self::new (BorderType::Top, gradient, foreground, width)
+	}
+
+	#[ inline ]
+	pub fn bottom (gradient: Gradient, foreground: Colour, width: usize) -> Self {
+		Self::new (BorderType::Bottom, gradient, foreground, width)
+	}
+
+	#[ inline ]
+	pub fn middle (gradient: Gradient, foreground: Colour, width: usize) -> Self {
+		Self::new (BorderType::Middle, gradient, foreground, width)
+	}
+
+}
+
+impl <'dat> Printable <'dat> for GradientBorder {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let Self { type_, gradient, foreground, width } = self;
+		for colour in gradient.sample (width) {
+			let (background, foreground) = match type_ {
+				BorderType::Top | BorderType::Bottom => (colour, foreground),
+				BorderType::Middle => (foreground, colour),
+			};
+			target.push (Attr::default ().background (background).foreground (foreground));
+			target.push (type_.symbol ());
+		}
+		target.push (Attr::default ());
+	}
+
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::ansi::{ AnsiTarget, ColourDepth };
+	use crate::grid::GridTarget;
+
+	const RED: Colour = Colour::new (0xff, 0x00, 0x00);
+	const BLUE: Colour = Colour::new (0x00, 0x00, 0xff);
+	const WHITE: Colour = Colour::new (0xff, 0xff, 0xff);
+
+	#[ test ]
+	fn gradient_at () {
+		let gradient = Gradient::new (RED, [ WHITE, BLUE ]);
+		assert_eq! (RED, gradient.at (0.0));
+		assert_eq! (WHITE, gradient.at (0.5));
+		assert_eq! (BLUE, gradient.at (1.0));
+		assert_eq! (BLUE, gradient.at (2.0));
+		assert_eq! (RED.mix_oklab (WHITE, 0.5), gradient.at (0.25));
+		assert_eq! (vec! [ RED, WHITE, BLUE ], gradient.sample (3));
+		assert_eq! (vec! [ RED ], Gradient::new (RED, []).sample (1));
+	}
+
+	#[ test ]
+	fn gradient_text () {
+		let mut target = GridTarget::new (10);
+		target.push (GradientText::new ("abc", Gradient::new (RED, [ BLUE ])).attr (Attr::default ().bold (true)));
+		target.newline ();
+		assert_eq! ("abc\n", target.to_text ());
+		let style_map = target.to_style_map ();
+		assert! (style_map.starts_with ("ABC\n"), "{style_map}");
+		assert! (style_map.contains ("A: fg=#ff0000 bold\n"), "{style_map}");
+		assert! (style_map.contains ("C: fg=#0000ff bold\n"), "{style_map}");
+	}
+
+	#[ test ]
+	fn gradient_border () {
+		let mut target = GridTarget::new (4);
+		target.push (GradientBorder::middle (Gradient::new (RED, [ BLUE ]), WHITE, 4));
+		target.newline ();
+		assert_eq! ("────\n", target.to_text ());
+		assert! (target.to_style_map ().starts_with ("ABCD\n"));
+		assert_eq! (Some (RED.into ()), target.cell (0, 0).unwrap ().attr.foreground);
+		assert_eq! (Some (WHITE.into ()), target.cell (0, 3).unwrap ().attr.background);
+	}
+
+	#[ test ]
+	fn gradient_colour_depth () {
+		let mut target = AnsiTarget::new (Vec::new (), 80).with_colour_depth (ColourDepth::Ansi16);
+		target.push (GradientText::new ("abcd", Gradient::new (RED, [ Colour::new (0xee, 0x00, 0x00) ])));
+		assert_eq! (
+			"\x1b[0;91mabcd\x1b[0m",
+			String::from_utf8 (target.finish ().unwrap ()).unwrap ());
+	}
+
+}
@@ -0,0 +1,177 @@
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{ Attr, Target, TermColour, Underline };
+use crate::width;
+
+#[ derive (Clone, Debug, Eq, PartialEq) ]
+pub struct GridCell {
+	pub symbol: String,
+	pub attr: Attr,
+}
+
+pub struct GridTarget {
+	width: usize,
+	rows: Vec <Vec <GridCell>>,
+	attr: Attr,
+}
+
+impl GridTarget {
+
+	#[ inline ]
+	pub fn new (width: usize) -> Self {
+		Self {
+			width,
+			rows: vec! [ Vec::new () ],
+			attr: Attr::default (),
+		}
+	}
+
+	pub fn rows (& self) -> & [Vec <GridCell>] {
+		let num_rows = self.rows.len () - usize::from (self.rows.last ().unwrap ().is_empty ());
+		& self.rows [ .. num_rows]
+	}
+
+	#[ inline ]
+	pub fn cell (& self, row: usize, col: usize) -> Option <& GridCell> {
+		self.rows ().get (row).and_then (|row| row.get (col))
+	}
+
+	#[ inline ]
+	pub fn used_width (& self) -> usize {
+		self.rows ().iter ().map (Vec::len).max ().unwrap_or (0)
+	}
+
+	pub fn to_text (& self) -> String {
+		let mut text = String::new ();
+		for row in self.rows () {
+			for cell in row {
+				text.push_str (& cell.symbol);
+			}
+			text.push ('\n');
+		}
+		text
+	}
+
+	pub fn to_style_map (& self) -> String {
+		let mut styles: Vec <Attr> = Vec::new ();
+		let mut map = String::new ();
+		for row in self.rows () {
+			for cell in row {
+				if cell.attr == Attr::default () {
+					map.push ('.');
+					continue;
+				}
+				let idx = styles.iter ().position (|& attr| attr == cell.attr).unwrap_or_else (|| {
+					styles.push (cell.attr);
+					styles.len () - 1
+				});
+				map.push (style_key (idx));
+			}
+			map.push ('\n');
+		}
+		for (idx, & attr) in styles.iter ().enumerate () {
+			writeln! (map, "{}: {}", style_key (idx), describe_attr (attr)).unwrap ();
+		}
+		map
+	}
+
+}
+
+impl <'dat> Target <'dat> for GridTarget {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.attr = attr;
+	}
+
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		let row = self.rows.last_mut ().unwrap ();
+		for grapheme in text.graphemes (true) {
+			let width = width::grapheme_width (grapheme);
+			if width == 0 {
+				if let Some (cell) = row.iter_mut ().rev ().find (|cell| ! cell.symbol.is_empty ()) {
+					cell.symbol.push_str (grapheme);
+				}
+				continue;
+			}
+			row.push (GridCell { symbol: grapheme.to_owned (), attr: self.attr });
+			for _ in 1 .. width {
+				row.push (GridCell { symbol: String::new (), attr: self.attr });
+			}
+		}
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.rows.push (Vec::new ());
+	}
+
+}
+
+fn style_key (idx: usize) -> char {
+	const KEYS: & [u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+	KEYS.get (idx).map_or ('?', |& key| key as char)
+}
+
+fn describe_attr (attr: Attr) -> String {
+	let mut parts = Vec::new ();
+	let colour = |name: & str, colour: TermColour| {
+		format! ("{name}={colour}")
+	};
+	if let Some (foreground) = attr.foreground { parts.push (colour ("fg", foreground)) }
+	if let Some (background) = attr.background { parts.push (colour ("bg", background)) }
+	if let Some (underline_colour) = attr.underline_colour { parts.push (colour ("ul", underline_colour)) }
+	match attr.underline {
+		Underline::None => (),
+		Underline::Single => parts.push ("underline".to_owned ()),
+		Underline::Double => parts.push ("underline=double".to_owned ()),
+		Underline::Curly => parts.push ("underline=curly".to_owned ()),
+		Underline::Dotted => parts.push ("underline=dotted".to_owned ()),
+		Underline::Dashed => parts.push ("underline=dashed".to_owned ()),
+	}
+	for (enabled, name) in [
+		(attr.bold, "bold"),
+		(attr.dim, "dim"),
+		(attr.italic, "italic"),
+		(attr.blink, "blink"),
+		(attr.reverse, "reverse"),
+		(attr.hidden, "hidden"),
+		(attr.strikethrough, "strikethrough"),
+	] {
+		if enabled { parts.push (name.to_owned ()) }
+	}
+	parts.join (" ")
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Colour;
+
+	#[ test ]
+	fn grid_cells () {
+		let mut target = GridTarget::new (10);
+		target.push ("a日");
+		target.push (Attr::default ().bold (true).foreground (Colour::new (1, 2, 3)));
+		target.push ("e\u{301}");
+		target.newline ();
+		target.push (Attr::default ());
+		target.push ("b");
+		target.newline ();
+		assert_eq! (2, target.rows ().len ());
+		assert_eq! (4, target.rows () [0].len ());
+		assert_eq! (4, target.used_width ());
+		assert_eq! ("", target.cell (0, 2).unwrap ().symbol);
+		assert_eq! ("a日e\u{301}\nb\n", target.to_text ());
+		assert_eq! ("...A\n.\nA: fg=#010203 bold\n", target.to_style_map ());
+	}
+
+}
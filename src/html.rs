@@ -0,0 +1,297 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::{ self, Write as _ };
+use std::io;
+
+use crate::{ Attr, Colour, Target, TermColour, Underline };
+use crate::colour::ColoursMap;
+use crate::output::{ FmtOutput, IoOutput, Output };
+
+pub struct HtmlTarget <Out: Output> {
+	out: Out,
+	width: usize,
+	classes: Option <HashMap <Colour, String>>,
+	attr: Attr,
+	span: Option <Attr>,
+	link: bool,
+	started: bool,
+	error: Option <io::Error>,
+}
+
+impl <Out: io::Write> HtmlTarget <IoOutput <Out>> {
+
+	#[ inline ]
+	pub fn new (out: Out, width: usize) -> Self {
+		Self::with_output (IoOutput::new (out), width)
+	}
+
+}
+
+impl <Out: fmt::Write> HtmlTarget <FmtOutput <Out>> {
+
+	#[ inline ]
+	pub fn new_fmt (out: Out, width: usize) -> Self {
+		Self::with_output (FmtOutput::new (out), width)
+	}
+
+}
+
+impl <Out: Output> HtmlTarget <Out> {
+
+	#[ inline ]
+	pub fn with_output (out: Out, width: usize) -> Self {
+		Self {
+			out,
+			width,
+			classes: None,
+			attr: Attr::default (),
+			span: None,
+			link: false,
+			started: false,
+			error: None,
+		}
+	}
+
+	pub fn with_classes (mut self, colours: & ColoursMap) -> Self {
+		let mut classes: HashMap <Colour, String> = HashMap::new ();
+		for (name, & colour) in colours.iter () {
+			let name = class_name (name);
+			match classes.get (& colour) {
+				Some (existing) if * existing <= name => (),
+				_ => { classes.insert (colour, name); },
+			}
+		}
+		self.classes = Some (classes);
+		self
+	}
+
+	#[ inline ]
+	pub fn error (& self) -> Option <& io::Error> {
+		self.error.as_ref ()
+	}
+
+	pub fn finish (mut self) -> io::Result <Out::Inner> {
+		self.start ();
+		self.close_span ();
+		if self.link { self.write ("</a>") }
+		self.write ("</pre>\n");
+		if let Some (err) = self.error.take () { return Err (err) }
+		self.out.flush () ?;
+		Ok (self.out.into_inner ())
+	}
+
+	fn start (& mut self) {
+		if self.started { return }
+		self.started = true;
+		self.write ("<pre class=\"jp-term\">");
+	}
+
+	fn close_span (& mut self) {
+		if self.span.take ().is_some () { self.write ("</span>") }
+	}
+
+	fn open_span (& mut self) {
+		if self.span == Some (self.attr) { return }
+		if self.span.is_some () { self.write ("</span>") }
+		if self.attr == Attr::default () {
+			self.span = None;
+			return;
+		}
+		let (classes, style) = self.attr_html (self.attr);
+		let mut buf = String::from ("<span");
+		if ! classes.is_empty () { write! (buf, " class=\"{}\"", classes.join (" ")).unwrap () }
+		if ! style.is_empty () { write! (buf, " style=\"{}\"", style.join (";")).unwrap () }
+		buf.push ('>');
+		self.write (& buf);
+		self.span = Some (self.attr);
+	}
+
+	fn attr_html (& self, attr: Attr) -> (Vec <String>, Vec <String>) {
+		let mut classes = Vec::new ();
+		let mut style = Vec::new ();
+		let (foreground, background) = if attr.reverse {
+			(attr.background, attr.foreground)
+		} else {
+			(attr.foreground, attr.background)
+		};
+		let mut colour = |prefix: & str, property: & str, colour: Option <TermColour>| {
+			let Some (colour) = colour.map (TermColour::to_rgb) else { return };
+			match self.classes.as_ref ().and_then (|classes| classes.get (& colour)) {
+				Some (name) => classes.push (format! ("{prefix}-{name}")),
+				None => style.push (format! ("{property}:{}", colour)),
+			}
+		};
+		colour ("fg", "color", foreground);
+		colour ("bg", "background-color", background);
+		colour ("ul", "text-decoration-color", attr.underline_colour);
+		let mut decoration = Vec::new ();
+		if attr.underline != Underline::None { decoration.push ("underline") }
+		if attr.strikethrough { decoration.push ("line-through") }
+		if attr.blink { decoration.push ("blink") }
+		if ! decoration.is_empty () {
+			style.push (format! ("text-decoration-line:{}", decoration.join (" ")));
+		}
+		match attr.underline {
+			Underline::None | Underline::Single => (),
+			Underline::Double => style.push ("text-decoration-style:double".to_owned ()),
+			Underline::Curly => style.push ("text-decoration-style:wavy".to_owned ()),
+			Underline::Dotted => style.push ("text-decoration-style:dotted".to_owned ()),
+			Underline::Dashed => style.push ("text-decoration-style:dashed".to_owned ()),
+		}
+		if attr.bold { style.push ("font-weight:bold".to_owned ()) }
+		if attr.dim { style.push ("opacity:0.5".to_owned ()) }
+		if attr.italic { style.push ("font-style:italic".to_owned ()) }
+		if attr.hidden { style.push ("visibility:hidden".to_owned ()) }
+		(classes, style)
+	}
+
+	fn write (& mut self, text: & str) {
+		if self.error.is_some () { return }
+		if let Err (err) = self.out.write_str (text) {
+			self.error = Some (err);
+		}
+	}
+
+}
+
+impl <'dat, Out: Output> Target <'dat> for HtmlTarget <Out> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.attr = attr;
+	}
+
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		if text.is_empty () { return }
+		self.start ();
+		self.open_span ();
+		self.write (& escape (& text));
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.start ();
+		self.write ("\n");
+	}
+
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		self.start ();
+		self.close_span ();
+		if self.link { self.write ("</a>") }
+		self.write (& format! ("<a href=\"{}\">", escape (& url)));
+		self.link = true;
+	}
+
+	fn end_link (& mut self) {
+		if ! self.link { return }
+		self.close_span ();
+		self.write ("</a>");
+		self.link = false;
+	}
+
+}
+
+pub fn stylesheet (colours: & ColoursMap) -> String {
+	let mut names: Vec <_> = colours.iter ().collect ();
+	names.sort_by_key (|& (name, _)| name);
+	let mut css = String::new ();
+	for (name, & colour) in names {
+		let name = class_name (name);
+		writeln! (css, ".jp-term .fg-{name} {{ color: {colour}; }}").unwrap ();
+		writeln! (css, ".jp-term .bg-{name} {{ background-color: {colour}; }}").unwrap ();
+		writeln! (css, ".jp-term .ul-{name} {{ text-decoration-color: {colour}; }}").unwrap ();
+	}
+	css
+}
+
+pub fn escape (text: & str) -> Cow <'_, str> {
+	if ! text.contains (['&', '<', '>', '"', '\'']) { return Cow::Borrowed (text) }
+	let mut buf = String::with_capacity (text.len () + 16);
+	for ch in text.chars () {
+		match ch {
+			'&' => buf.push_str ("&amp;"),
+			'<' => buf.push_str ("&lt;"),
+			'>' => buf.push_str ("&gt;"),
+			'"' => buf.push_str ("&quot;"),
+			'\'' => buf.push_str ("&#39;"),
+			_ => buf.push (ch),
+		}
+	}
+	Cow::Owned (buf)
+}
+
+fn class_name (name: & str) -> String {
+	name.chars ()
+		.map (|ch| if ch.is_ascii_alphanumeric () || ch == '-' || ch == '_' { ch } else { '-' })
+		.collect ()
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Link;
+
+	#[ test ]
+	fn html_spans () {
+		let red = Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00));
+		let mut target = HtmlTarget::new_fmt (String::new (), 80);
+		target.push ("a<b");
+		target.push (red);
+		target.push ("c");
+		target.push (red);
+		target.push ("d");
+		target.newline ();
+		target.push (red.bold (true).underline (Underline::Curly));
+		target.push ("\"e\"");
+		target.push (Attr::default ());
+		target.push ("&");
+		assert_eq! (
+			concat! (
+				"<pre class=\"jp-term\">a&lt;b<span style=\"color:#ff0000\">cd\n",
+				"</span><span style=\"color:#ff0000;text-decoration-line:underline;",
+				"text-decoration-style:wavy;font-weight:bold\">&quot;e&quot;</span>&amp;</pre>\n"),
+			target.finish ().unwrap ());
+	}
+
+	#[ test ]
+	fn html_links () {
+		let mut target = HtmlTarget::new_fmt (String::new (), 80);
+		target.push (Attr::default ().bold (true));
+		target.push ("a ");
+		target.push (Link::new ("https://example.com/?a&b", "b"));
+		target.push (" c");
+		assert_eq! (
+			concat! (
+				"<pre class=\"jp-term\"><span style=\"font-weight:bold\">a </span>",
+				"<a href=\"https://example.com/?a&amp;b\"><span style=\"font-weight:bold\">b</span></a>",
+				"<span style=\"font-weight:bold\"> c</span></pre>\n"),
+			target.finish ().unwrap ());
+	}
+
+	#[ test ]
+	fn html_classes () -> anyhow::Result <()> {
+		let colours = ColoursMap::build ([
+			("red", "#ff0000"),
+			("error fg", "red"),
+			("black", "#000000"),
+		]) ?;
+		let mut target = HtmlTarget::new_fmt (String::new (), 80).with_classes (& colours);
+		target.push (Attr::default ()
+			.foreground (Colour::new (0xff, 0x00, 0x00))
+			.background (Colour::new (0x00, 0x00, 0x00))
+			.reverse (true));
+		target.push ("x");
+		assert_eq! (
+			"<pre class=\"jp-term\"><span class=\"fg-black bg-error-fg\">x</span></pre>\n",
+			target.finish () ?);
+		assert! (stylesheet (& colours).starts_with (".jp-term .fg-black { color: #000000; }\n"));
+		Ok (())
+	}
+
+}
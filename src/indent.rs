@@ -0,0 +1,171 @@
+use std::borrow::Cow;
+
+use crate::{ Attr, Printable, Target };
+use crate::width;
+
+pub struct IndentTarget <'tar, 'dat, Inner: Target <'dat>> {
+	inner: & 'tar mut Inner,
+	prefix: Cow <'dat, str>,
+	prefix_attr: Attr,
+	prefix_width: usize,
+	line_start: bool,
+	attr: Attr,
+	link: Option <Cow <'dat, str>>,
+}
+
+impl <'tar, 'dat, Inner: Target <'dat>> IndentTarget <'tar, 'dat, Inner> {
+
+	#[ inline ]
+	pub fn new (inner: & 'tar mut Inner, indent: usize) -> Self {
+		Self::with_gutter (inner, " ".repeat (indent), Attr::default ())
+	}
+
+	pub fn with_gutter (
+		inner: & 'tar mut Inner,
+		prefix: impl Into <Cow <'dat, str>>,
+		prefix_attr: Attr,
+	) -> Self {
+		let prefix = prefix.into ();
+		let prefix_width = width::str_width (& prefix);
+		Self {
+			inner,
+			prefix,
+			prefix_attr,
+			prefix_width,
+			line_start: true,
+			attr: Attr::default (),
+			link: None,
+		}
+	}
+
+	fn start_line (& mut self) {
+		if ! self.line_start { return }
+		self.line_start = false;
+		if self.link.is_some () { self.inner.end_link () }
+		self.inner.push_attr (self.prefix_attr);
+		self.inner.push_str (self.prefix.clone ());
+		self.inner.push_attr (self.attr);
+		if let Some (url) = self.link.clone () { self.inner.begin_link (url) }
+	}
+
+}
+
+impl <'tar, 'dat, Inner: Target <'dat>> Target <'dat> for IndentTarget <'tar, 'dat, Inner> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.inner.width ().saturating_sub (self.prefix_width)
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.attr = attr;
+		if ! self.line_start { self.inner.push_attr (attr) }
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		self.start_line ();
+		self.inner.push_str (text);
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.start_line ();
+		self.inner.newline ();
+		self.line_start = true;
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		self.link = Some (url.clone ());
+		if ! self.line_start { self.inner.begin_link (url) }
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+		self.link = None;
+		if ! self.line_start { self.inner.end_link () }
+	}
+
+}
+
+pub struct Indented <'dat, Inner: Printable <'dat>> {
+	inner: Inner,
+	prefix: Cow <'dat, str>,
+	prefix_attr: Attr,
+}
+
+impl <'dat, Inner: Printable <'dat>> Indented <'dat, Inner> {
+
+	#[ inline ]
+	pub fn new (indent: usize, inner: Inner) -> Self {
+		Self::with_gutter (" ".repeat (indent), Attr::default (), inner)
+	}
+
+	#[ inline ]
+	pub fn with_gutter (
+		prefix: impl Into <Cow <'dat, str>>,
+		prefix_attr: Attr,
+		inner: Inner,
+	) -> Self {
+		Self { inner, prefix: prefix.into (), prefix_attr }
+	}
+
+}
+
+impl <'dat, Inner: Printable <'dat>> Printable <'dat> for Indented <'dat, Inner> {
+
+	#[ inline ]
+	fn print (self, target: & mut impl Target <'dat>) {
+		let mut indent_target = IndentTarget::with_gutter (target, self.prefix, self.prefix_attr);
+		indent_target.push (self.inner);
+	}
+
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Colour;
+	use crate::grid::GridTarget;
+	use crate::border_box::TEST_BOX_STYLE;
+	use crate::message_box::MessageBox;
+
+	#[ test ]
+	fn nested_indent () {
+		let bar = Attr::default ().foreground (Colour::new (0x80, 0x80, 0x80));
+		let red = Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00));
+		let mut target = GridTarget::new (20);
+		let mut outer = IndentTarget::with_gutter (& mut target, "│ ", bar);
+		assert_eq! (18, outer.width ());
+		let mut inner = IndentTarget::new (& mut outer, 2);
+		assert_eq! (16, inner.width ());
+		inner.push (red);
+		inner.push ("one");
+		inner.newline ();
+		inner.newline ();
+		inner.push ("two");
+		inner.push (Attr::default ());
+		inner.newline ();
+		assert_eq! ("│   one\n│   \n│   two\n", target.to_text ());
+		assert_eq! ("AA..BBB\nAA..\nAA..BBB\nA: fg=#808080\nB: fg=#ff0000\n", target.to_style_map ());
+	}
+
+	#[ test ]
+	fn indented_message_box () {
+		let mut target = GridTarget::new (12);
+		target.push (Indented::new (3, MessageBox::new (TEST_BOX_STYLE, "abcdefghijk")));
+		assert_eq! (
+			concat! (
+				"    ▅▅▅▅▅▅▅▅\n",
+				"      abcd  \n",
+				"      efgh  \n",
+				"      ijk   \n",
+				"    🮄🮄🮄🮄🮄🮄🮄🮄\n"),
+			target.to_text ());
+		assert_eq! (12, target.used_width ());
+	}
+
+}
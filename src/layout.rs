@@ -0,0 +1,429 @@
+use std::cmp;
+use std::iter;
+
+use crate::{ Attr, Printable, Target };
+use crate::buffer::{ self, BufferLine, BufferTarget, Render };
+use crate::table::Align;
+
+#[ derive (Clone, Copy, Debug, Eq, PartialEq) ]
+pub enum ColumnWidth {
+	Fixed (usize),
+	Flex,
+}
+
+pub struct HStack <'dat> {
+	gap: usize,
+	children: Vec <(ColumnWidth, Render <'dat>)>,
+}
+
+impl <'dat> HStack <'dat> {
+
+	#[ inline ]
+	pub fn new () -> Self {
+		Self { gap: 1, children: Vec::new () }
+	}
+
+	#[ inline ]
+	pub fn gap (mut self, gap: usize) -> Self {
+		self.gap = gap;
+		self
+	}
+
+	#[ inline ]
+	pub fn fixed (mut self, width: usize, value: impl Printable <'dat> + 'dat) -> Self {
+		self.children.push ((ColumnWidth::Fixed (width), buffer::render (value)));
+		self
+	}
+
+	#[ inline ]
+	pub fn flex (mut self, value: impl Printable <'dat> + 'dat) -> Self {
+		self.children.push ((ColumnWidth::Flex, buffer::render (value)));
+		self
+	}
+
+	fn column_widths (& self, total_width: usize) -> Vec <usize> {
+		let gaps = self.gap * self.children.len ().saturating_sub (1);
+		let fixed: usize = self.children.iter ()
+			.filter_map (|& (width, _)| match width {
+				ColumnWidth::Fixed (width) => Some (width),
+				ColumnWidth::Flex => None,
+			})
+			.sum ();
+		let num_flex = self.children.iter ()
+			.filter (|& & (width, _)| width == ColumnWidth::Flex)
+			.count ();
+		let remain = total_width.saturating_sub (fixed + gaps);
+		let mut flex_idx = 0;
+		self.children.iter ()
+			.map (|& (width, _)| match width {
+				ColumnWidth::Fixed (width) => width,
+				ColumnWidth::Flex => {
+					let width = remain / num_flex + usize::from (flex_idx < remain % num_flex);
+					flex_idx += 1;
+					width
+				},
+			})
+			.collect ()
+	}
+
+}
+
+impl <'dat> Default for HStack <'dat> {
+
+	#[ inline ]
+	fn default () -> Self {
+		Self::new ()
+	}
+
+}
+
+impl <'dat> Printable <'dat> for HStack <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let widths = self.column_widths (target.width ());
+		let gap = self.gap;
+		let mut columns: Vec <(usize, Vec <BufferLine <'dat>>)> = iter::zip (widths, self.children)
+			.map (|(width, (_, render))| (width, BufferTarget::render_clipped (width, render)))
+			.collect ();
+		let height = columns.iter ().map (|(_, lines)| lines.len ()).max ().unwrap_or (0);
+		for (_, lines) in & mut columns {
+			lines.resize_with (height, BufferLine::default);
+		}
+		let mut columns: Vec <_> = columns.into_iter ()
+			.map (|(width, lines)| (width, lines.into_iter ()))
+			.collect ();
+		let num_columns = columns.len ();
+		for _ in 0 .. height {
+			for (idx, (width, lines)) in columns.iter_mut ().enumerate () {
+				if idx > 0 { target.push (" ".repeat (gap)) }
+				let line = lines.next ().unwrap ();
+				let line_width = line.width ();
+				line.replay (target);
+				target.push (Attr::default ());
+				if idx + 1 < num_columns {
+					target.push (" ".repeat (width.saturating_sub (line_width)));
+				}
+			}
+			target.newline ();
+		}
+	}
+
+}
+
+pub struct VStack <'dat> {
+	spacing: usize,
+	children: Vec <Render <'dat>>,
+}
+
+impl <'dat> VStack <'dat> {
+
+	#[ inline ]
+	pub fn new () -> Self {
+		Self { spacing: 0, children: Vec::new () }
+	}
+
+	#[ inline ]
+	pub fn spacing (mut self, spacing: usize) -> Self {
+		self.spacing = spacing;
+		self
+	}
+
+	#[ inline ]
+	pub fn child (mut self, value: impl Printable <'dat> + 'dat) -> Self {
+		self.children.push (buffer::render (value));
+		self
+	}
+
+}
+
+impl <'dat> Default for VStack <'dat> {
+
+	#[ inline ]
+	fn default () -> Self {
+		Self::new ()
+	}
+
+}
+
+impl <'dat> Printable <'dat> for VStack <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		for (idx, render) in self.children.into_iter ().enumerate () {
+			if idx > 0 {
+				for _ in 0 .. self.spacing { target.newline () }
+			}
+			let mut buffer = BufferTarget::new (target.width ());
+			render (& mut buffer);
+			for line in buffer.into_lines () {
+				line.replay (target);
+				target.newline ();
+			}
+		}
+	}
+
+}
+
+pub struct Padding <'dat> {
+	top: usize,
+	right: usize,
+	bottom: usize,
+	left: usize,
+	fill: Attr,
+	inner: Render <'dat>,
+}
+
+impl <'dat> Padding <'dat> {
+
+	#[ inline ]
+	pub fn new (inner: impl Printable <'dat> + 'dat) -> Self {
+		Self {
+			top: 0,
+			right: 0,
+			bottom: 0,
+			left: 0,
+			fill: Attr::default (),
+			inner: buffer::render (inner),
+		}
+	}
+
+	#[ inline ]
+	pub fn top (mut self, top: usize) -> Self {
+		self.top = top;
+		self
+	}
+
+	#[ inline ]
+	pub fn right (mut self, right: usize) -> Self {
+		self.right = right;
+		self
+	}
+
+	#[ inline ]
+	pub fn bottom (mut self, bottom: usize) -> Self {
+		self.bottom = bottom;
+		self
+	}
+
+	#[ inline ]
+	pub fn left (mut self, left: usize) -> Self {
+		self.left = left;
+		self
+	}
+
+	#[ inline ]
+	pub fn vertical (self, padding: usize) -> Self {
+		self.top (padding).bottom (padding)
+	}
+
+	#[ inline ]
+	pub fn horizontal (self, padding: usize) -> Self {
+		self.left (padding).right (padding)
+	}
+
+	#[ inline ]
+	pub fn fill (mut self, fill: Attr) -> Self {
+		self.fill = fill;
+		self
+	}
+
+}
+
+impl <'dat> Printable <'dat> for Padding <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let Self { top, right, bottom, left, fill, inner } = self;
+		let inner_width = target.width ().saturating_sub (left + right);
+		let lines = BufferTarget::render_clipped (inner_width, inner);
+		let content_width = lines.iter ().map (BufferLine::width).max ().unwrap_or (0);
+		let full_width = left + content_width + right;
+		let fill_attr = |attr: Attr| match (attr.background, fill.background) {
+			(None, Some (background)) => attr.background_term (background),
+			_ => attr,
+		};
+		for _ in 0 .. top {
+			target.push (fill);
+			target.push (" ".repeat (full_width));
+			target.push (Attr::default ());
+			target.newline ();
+		}
+		for line in lines {
+			let line_width = line.width ();
+			target.push (fill);
+			target.push (" ".repeat (left));
+			line.replay_with (target, fill_attr);
+			target.push (fill);
+			target.push (" ".repeat (content_width - line_width + right));
+			target.push (Attr::default ());
+			target.newline ();
+		}
+		for _ in 0 .. bottom {
+			target.push (fill);
+			target.push (" ".repeat (full_width));
+			target.push (Attr::default ());
+			target.newline ();
+		}
+	}
+
+}
+
+pub struct Aligned <'dat> {
+	align: Align,
+	inner: Render <'dat>,
+}
+
+impl <'dat> Aligned <'dat> {
+
+	#[ inline ]
+	pub fn new (align: Align, inner: impl Printable <'dat> + 'dat) -> Self {
+		Self { align, inner: buffer::render (inner) }
+	}
+
+}
+
+impl <'dat> Printable <'dat> for Aligned <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let width = target.width ();
+		let lines = BufferTarget::render_clipped (width, self.inner);
+		let block_width = lines.iter ().map (BufferLine::width).max ().unwrap_or (0);
+		let offset = match self.align {
+			Align::Left => 0,
+			Align::Centre => (width - block_width) / 2,
+			Align::Right => width - block_width,
+		};
+		for line in lines {
+			if offset > 0 && ! line.is_empty () { target.push (" ".repeat (offset)) }
+			line.replay (target);
+			target.newline ();
+		}
+	}
+
+}
+
+pub struct Constrained <'dat> {
+	min_width: usize,
+	max_width: usize,
+	inner: Render <'dat>,
+}
+
+impl <'dat> Constrained <'dat> {
+
+	#[ inline ]
+	pub fn new (inner: impl Printable <'dat> + 'dat) -> Self {
+		Self { min_width: 0, max_width: usize::MAX, inner: buffer::render (inner) }
+	}
+
+	#[ inline ]
+	pub fn min_width (mut self, min_width: usize) -> Self {
+		self.min_width = min_width;
+		self
+	}
+
+	#[ inline ]
+	pub fn max_width (mut self, max_width: usize) -> Self {
+		self.max_width = max_width;
+		self
+	}
+
+}
+
+impl <'dat> Printable <'dat> for Constrained <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		let width = cmp::max (cmp::min (target.width (), self.max_width), self.min_width);
+		for line in BufferTarget::render_clipped (width, self.inner) {
+			let line_width = line.width ();
+			line.replay (target);
+			if line_width < self.min_width {
+				target.push (Attr::default ());
+				target.push (" ".repeat (self.min_width - line_width));
+			}
+			target.newline ();
+		}
+	}
+
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Colour;
+	use crate::border_box::TEST_BOX_STYLE;
+	use crate::grid::GridTarget;
+	use crate::message_box::MessageBox;
+
+	#[ test ]
+	fn hstack_columns () {
+		let mut target = GridTarget::new (32);
+		target.push (HStack::new ()
+			.gap (2)
+			.fixed (8, MessageBox::new (TEST_BOX_STYLE, "hi"))
+			.flex (MessageBox::new (TEST_BOX_STYLE, "one\ntwo\nthree"))
+			.flex ("a very long line which gets clipped"));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅    ▅▅▅▅▅▅▅▅▅  a very lon\n",
+				"   hi        one      \n",
+				" 🮄🮄🮄🮄🮄🮄      two      \n",
+				"             three    \n",
+				"           🮄🮄🮄🮄🮄🮄🮄🮄🮄  \n"),
+			target.to_text ());
+	}
+
+	#[ test ]
+	fn layout_wrapped_message_box () {
+		let mut target = GridTarget::new (30);
+		target.push (HStack::new ()
+			.flex (MessageBox::new (TEST_BOX_STYLE, "hello world this is long text"))
+			.flex ("x"));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅▅▅▅▅▅▅▅▅ x\n",
+				"   hello worl   \n",
+				"   d this is    \n",
+				"   long text    \n",
+				" 🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄 \n"),
+			target.to_text ());
+		let mut target = GridTarget::new (12);
+		target.push (Padding::new (MessageBox::new (TEST_BOX_STYLE, "abcdefghijklmnop")).horizontal (1));
+		assert_eq! (
+			concat! (
+				"  ▅▅▅▅▅▅▅▅▅ \n",
+				"    abcde   \n",
+				"    fghij   \n",
+				"    klmno   \n",
+				"    p       \n",
+				"  🮄🮄🮄🮄🮄🮄🮄🮄🮄 \n"),
+			target.to_text ());
+	}
+
+	#[ test ]
+	fn vstack_layout () {
+		let fill = Attr::default ().background (Colour::new (0x00, 0x00, 0xff));
+		let mut target = GridTarget::new (12);
+		target.push (VStack::new ()
+			.spacing (1)
+			.child (Aligned::new (Align::Right, Constrained::new ("abcdefgh").max_width (4)))
+			.child (Aligned::new (Align::Centre, Padding::new (Constrained::new ("ab").min_width (4))
+				.horizontal (1)
+				.top (1)
+				.fill (fill))));
+		assert_eq! (
+			concat! (
+				"        abcd\n",
+				"\n",
+				"         \n",
+				"    ab   \n"),
+			target.to_text ());
+		assert_eq! (
+			concat! (
+				"............\n",
+				"\n",
+				"...AAAAAA\n",
+				"...AAAAAA\n",
+				"A: bg=#0000ff\n"),
+			target.to_style_map ());
+	}
+
+}
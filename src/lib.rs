@@ -1,14 +1,34 @@
 use std::borrow::Cow;
+use std::env;
+use std::io::{ self, Write };
+use std::time::Duration;
 
 pub mod ansi;
+pub mod background;
 pub mod colour;
+pub mod colour_names;
 pub mod border_box;
+pub mod buffer;
+pub mod clip;
+pub mod gradient;
+pub mod grid;
+pub mod html;
+pub mod indent;
+pub mod layout;
+pub mod markup;
+pub mod measure;
 pub mod message_box;
+pub mod output;
+pub mod plain;
 pub mod ratatui;
+pub mod svg;
 pub mod table;
 pub mod tabs;
+pub mod theme;
+pub mod width;
 
-pub use colour::Colour;
+pub use background::{ Background, Brightness, QueryInput };
+pub use colour::{ AnsiColour, Colour, TermColour };
 
 pub trait Target <'dat>: Sized {
 
@@ -18,6 +38,14 @@ pub trait Target <'dat>: Sized {
 	fn push_str (& mut self, text: Cow <'dat, str>);
 	fn newline (& mut self);
 
+	#[ inline ]
+	fn begin_link (& mut self, _url: Cow <'dat, str>) {
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+	}
+
 	#[ inline ]
 	fn push (& mut self, value: impl Printable <'dat>) {
 		value.print (self);
@@ -50,11 +78,42 @@ impl <'dat> Printable <'dat> for Cow <'dat, str> {
 	}
 }
 
-#[ derive (Clone, Copy, Default) ]
+pub struct Link <'dat, Inner: Printable <'dat>> {
+	url: Cow <'dat, str>,
+	inner: Inner,
+}
+
+impl <'dat, Inner: Printable <'dat>> Link <'dat, Inner> {
+
+	#[ inline ]
+	pub fn new (url: impl Into <Cow <'dat, str>>, inner: Inner) -> Self {
+		Self { url: url.into (), inner }
+	}
+
+}
+
+impl <'dat, Inner: Printable <'dat>> Printable <'dat> for Link <'dat, Inner> {
+	#[ inline ]
+	fn print (self, target: & mut impl Target <'dat>) {
+		target.begin_link (self.url);
+		target.push (self.inner);
+		target.end_link ();
+	}
+}
+
+#[ derive (Clone, Copy, Debug, Default, Eq, PartialEq) ]
 pub struct Attr {
-	foreground: Option <Colour>,
-	background: Option <Colour>,
+	foreground: Option <TermColour>,
+	background: Option <TermColour>,
+	underline_colour: Option <TermColour>,
+	underline: Underline,
 	bold: bool,
+	dim: bool,
+	italic: bool,
+	blink: bool,
+	reverse: bool,
+	hidden: bool,
+	strikethrough: bool,
 }
 
 impl Attr {
@@ -64,28 +123,132 @@ impl Attr {
 		Self {
 			foreground: None,
 			background: None,
+			underline_colour: None,
+			underline: Underline::None,
 			bold: false,
+			dim: false,
+			italic: false,
+			blink: false,
+			reverse: false,
+			hidden: false,
+			strikethrough: false,
 		}
 	}
 
 	#[ inline ]
 	pub const fn foreground (mut self, foreground: Colour) -> Self {
+		self.foreground = Some (TermColour::Rgb (foreground));
+		self
+	}
+
+	#[ inline ]
+	pub const fn foreground_term (mut self, foreground: TermColour) -> Self {
 		self.foreground = Some (foreground);
 		self
 	}
 
 	#[ inline ]
 	pub const fn background (mut self, background: Colour) -> Self {
+		self.background = Some (TermColour::Rgb (background));
+		self
+	}
+
+	#[ inline ]
+	pub const fn background_term (mut self, background: TermColour) -> Self {
 		self.background = Some (background);
 		self
 	}
 
+	#[ inline ]
+	pub const fn underline_colour (mut self, underline_colour: Colour) -> Self {
+		self.underline_colour = Some (TermColour::Rgb (underline_colour));
+		self
+	}
+
+	#[ inline ]
+	pub const fn underline_colour_term (mut self, underline_colour: TermColour) -> Self {
+		self.underline_colour = Some (underline_colour);
+		self
+	}
+
+	#[ inline ]
+	pub const fn underline (mut self, underline: Underline) -> Self {
+		self.underline = underline;
+		self
+	}
+
 	#[ inline ]
 	pub const fn bold (mut self, bold: bool) -> Self {
 		self.bold = bold;
 		self
 	}
 
+	#[ inline ]
+	pub const fn dim (mut self, dim: bool) -> Self {
+		self.dim = dim;
+		self
+	}
+
+	#[ inline ]
+	pub const fn italic (mut self, italic: bool) -> Self {
+		self.italic = italic;
+		self
+	}
+
+	#[ inline ]
+	pub const fn blink (mut self, blink: bool) -> Self {
+		self.blink = blink;
+		self
+	}
+
+	#[ inline ]
+	pub const fn reverse (mut self, reverse: bool) -> Self {
+		self.reverse = reverse;
+		self
+	}
+
+	#[ inline ]
+	pub const fn hidden (mut self, hidden: bool) -> Self {
+		self.hidden = hidden;
+		self
+	}
+
+	#[ inline ]
+	pub const fn strikethrough (mut self, strikethrough: bool) -> Self {
+		self.strikethrough = strikethrough;
+		self
+	}
+
+	pub fn modifier (self, modifier: & str) -> Option <Self> {
+		Some (match modifier {
+			"bold" => self.bold (true),
+			"dim" => self.dim (true),
+			"italic" => self.italic (true),
+			"blink" => self.blink (true),
+			"reverse" => self.reverse (true),
+			"hidden" => self.hidden (true),
+			"strike" | "strikethrough" => self.strikethrough (true),
+			"underline" | "underline=single" => self.underline (Underline::Single),
+			"underline=none" => self.underline (Underline::None),
+			"underline=double" => self.underline (Underline::Double),
+			"underline=curly" => self.underline (Underline::Curly),
+			"underline=dotted" => self.underline (Underline::Dotted),
+			"underline=dashed" => self.underline (Underline::Dashed),
+			_ => return None,
+		})
+	}
+
+}
+
+#[ derive (Clone, Copy, Debug, Default, Eq, PartialEq) ]
+pub enum Underline {
+	#[ default ]
+	None,
+	Single,
+	Double,
+	Curly,
+	Dotted,
+	Dashed,
 }
 
 impl <'dat> Printable <'dat> for Attr {
@@ -102,6 +265,7 @@ pub struct Term {
 
 impl Term {
 
+	#[ allow (clippy::new_without_default) ]
 	pub fn new () -> Self {
 		let size = termsize::get ();
 		Self {
@@ -114,44 +278,211 @@ impl Term {
 		print! ("\x1bc");
 	}
 
+	// Queries the terminal in raw mode, waiting for its full reply so nothing is left in stdin; any
+	// keys typed before the reply arrives are consumed
+	pub fn background (& self, timeout: Duration) -> Option <Background> {
+		let get_var = |name: & str| env::var (name).ok ();
+		#[ cfg (unix) ]
+		{
+			use std::io::IsTerminal as _;
+			if io::stdin ().is_terminal () && io::stdout ().is_terminal () {
+				let was_raw = crossterm::terminal::is_raw_mode_enabled ().unwrap_or (false);
+				if was_raw || crossterm::terminal::enable_raw_mode ().is_ok () {
+					let background = Self::background_with (
+						& mut background::StdinInput,
+						& mut io::stdout (),
+						timeout,
+						get_var);
+					if ! was_raw { let _ = crossterm::terminal::disable_raw_mode (); }
+					return background;
+				}
+			}
+		}
+		Self::background_with (& mut & [] [..], & mut io::sink (), Duration::ZERO, get_var)
+	}
+
+	pub fn background_with (
+		input: & mut impl QueryInput,
+		output: & mut impl Write,
+		timeout: Duration,
+		get_var: impl Fn (& str) -> Option <String>,
+	) -> Option <Background> {
+		background::query_background (input, output, timeout).ok ().flatten ()
+			.or_else (|| background::parse_colorfgbg (& get_var ("COLORFGBG") ?))
+			.map (Background::new)
+	}
+
 }
 
 #[ macro_export ]
 macro_rules! printable {
+
+	() => {};
+
 	(
-		$(
-			$name:ident <
-				$tar:lifetime
-				$( , $life:lifetime )*
-				$( , )?
-			> (
-				$target:ident
-				$( , $arg_name:ident : $arg_type:ty )*
-				$( , )?
-			) {
-				$( $body:tt )*
-			}
-		)*
+		@header $attrs:tt $vis:tt $name:ident <
+			$tar:lifetime
+			$( , $life:lifetime )*
+			$( , )?
+			$( ; $( $gen:ident ),* $( , )? )?
+		> (
+			$target:ident
+			$( , $arg_name:ident : $arg_type:ty )*
+			$( , )?
+		)
+		$( $rest:tt )*
+	) => {
+		$crate::printable! (
+			@where [
+				$attrs $vis $name [ $tar ] [ $( $life ),* ] [ $( $( $gen ),* )? ] [ $target ]
+				[ $( $arg_name : $arg_type ),* ]
+			] []
+			$( $rest )*
+		);
+	};
+
+	( @where $header:tt [ $( $where:tt )* ] { $( $body:tt )* } $( $rest:tt )* ) => {
+		$crate::printable! (@item $header [ $( $where )* ] { $( $body )* });
+		$crate::printable! ($( $rest )*);
+	};
+
+	( @where $header:tt [ $( $where:tt )* ] $next:tt $( $rest:tt )* ) => {
+		$crate::printable! (@where $header [ $( $where )* $next ] $( $rest )*);
+	};
+
+	(
+		@item [
+			[ $( $attr:tt )* ] [ $( $vis:tt )* ] $name:ident [ $tar:lifetime ] [ $( $life:lifetime ),* ]
+			[ $( $gen:ident ),* ] [ $target:ident ] [ $( $arg_name:ident : $arg_type:ty ),* ]
+		] [ $( $where:tt )* ] { $( $body:tt )* }
 	) => {
-		$(
 
-			pub struct $name <$($life),*> {
-				$( $arg_name: $arg_type, )*
+		$( $attr )*
+		$( $vis )* struct $name <$($life,)* $($gen,)*> {
+			$( $arg_name: $arg_type, )*
+		}
+
+		impl <$($life,)* $($gen,)*> $name <$($life,)* $($gen,)*> {
+			#[ allow (clippy::too_many_arguments) ]
+			#[ inline ]
+			$( $vis )* fn new ($( $arg_name: $arg_type ),*) -> Self {
+				Self { $($arg_name),* }
 			}
+		}
 
-			impl <$($life),*> $name <$($life),*> {
-				pub fn new ($( $arg_name: $arg_type ),*) -> Self {
-					Self { $($arg_name),* }
-				}
+		impl <$tar, $($life,)* $($gen,)*> $crate::Printable <$tar> for $name <$($life,)* $($gen,)*>
+			$( $where )*
+		{
+			fn print (self, $target: & mut impl $crate::Target <$tar>) {
+				let Self { $($arg_name),* } = self;
+				$($body)*
 			}
+		}
 
-			impl <'tar, $($life),*> term::Printable <'tar> for $name <$($life),*> {
-				fn print (self, $target: & mut impl term::Target <'tar>) {
-					let Self { $($arg_name),* } = self;
-					$($body)*
-				}
+	};
+
+	( $( #[ $meta:meta ] )* pub ( $( $restrict:tt )+ ) $name:ident $( $rest:tt )* ) => {
+		$crate::printable! (@header [ $( #[ $meta ] )* ] [ pub ( $( $restrict )+ ) ] $name $( $rest )*);
+	};
+
+	( $( #[ $meta:meta ] )* pub $name:ident $( $rest:tt )* ) => {
+		$crate::printable! (@header [ $( #[ $meta ] )* ] [ pub ] $name $( $rest )*);
+	};
+
+	( $( #[ $meta:meta ] )* $name:ident $( $rest:tt )* ) => {
+		$crate::printable! (@header [ $( #[ $meta ] )* ] [ pub ] $name $( $rest )*);
+	};
+
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::grid::GridTarget;
+
+	mod inner {
+
+		use std::fmt::Display;
+
+		use crate::Attr;
+
+		crate::printable! {
+
+			/// Prints a label followed by a value.
+			#[ derive (Clone) ]
+			pub LabelValue <'tar, 'dat> (target, label: & 'dat str, value: String) {
+				target.push (Attr::default ().bold (true));
+				target.push (label.to_owned ());
+				target.push (Attr::default ());
+				target.push (": ");
+				target.push (value);
+			}
+
+			pub(crate) Shown <'tar; Value> (target, value: Value) where Value: Display {
+				target.push (value.to_string ());
+			}
+
+			pub Both <'tar; Label, Value> (
+				target,
+				label: Label,
+				value: Value,
+			) where
+				Label: crate::Printable <'tar>,
+				Label: Clone,
+				Value: Display,
+			{
+				target.push (label);
+				target.push (format! ("={value}"));
+			}
+
+			Plain <'tar> (target, count: usize) {
+				target.push (format! ("#{count}"));
+			}
+
+			pub Bounded <'tar, 'dat; Value> (target, label: & 'dat str, value: Value)
+				where 'dat: 'tar, Value: Display + Clone
+			{
+				target.push (label);
+				target.push (format! (":{}", value.clone ()));
 			}
 
-		)*
+		}
+
+	}
+
+	#[ test ]
+	fn term_background () {
+		let background = |response: & [u8], colorfgbg: Option <& str>| {
+			let mut input = response;
+			let get_var = |name: & str| (name == "COLORFGBG").then (|| colorfgbg.map (str::to_owned)).flatten ();
+			Term::background_with (& mut input, & mut Vec::new (), Duration::from_millis (100), get_var)
+		};
+		assert_eq! (
+			Some (Background { colour: Colour::new (0xfd, 0xf6, 0xe3), brightness: Brightness::Light }),
+			background (b"\x1b]11;rgb:fdfd/f6f6/e3e3\x1b\\\x1b[?62c", Some ("15;0")));
+		assert_eq! (
+			Some (Background { colour: Colour::new (0x00, 0x00, 0x00), brightness: Brightness::Dark }),
+			background (b"\x1b[?62c", Some ("15;0")));
+		assert_eq! (None, background (b"", None));
 	}
+
+	#[ test ]
+	fn printable_macro () {
+		let mut target = GridTarget::new (80);
+		let label_value = inner::LabelValue::new ("a", "b".to_owned ());
+		target.push (label_value.clone ());
+		target.newline ();
+		target.push (inner::Shown::new (42));
+		target.newline ();
+		target.push (inner::Both::new ("x", 1.5));
+		target.newline ();
+		target.push (inner::Plain::new (3));
+		target.newline ();
+		target.push (inner::Bounded::new ("y", 2));
+		target.newline ();
+		assert_eq! ("a: b\n42\nx=1.5\n#3\ny:2\n", target.to_text ());
+		assert_eq! ("A...\n..\n.....\n..\n...\nA: bold\n", target.to_style_map ());
+	}
+
 }
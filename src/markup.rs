@@ -0,0 +1,280 @@
+use std::borrow::Cow;
+
+use crate::{ Attr, Colour, Printable, Target, colour, colour_names };
+use crate::colour::{ ColoursMap, ParseColourError };
+
+pub struct Markup <'dat> {
+	items: Vec <Item <'dat>>,
+}
+
+enum Item <'dat> {
+	Attr (Attr),
+	Text (Cow <'dat, str>),
+	Newline,
+	BeginLink (Cow <'dat, str>),
+	EndLink,
+}
+
+struct Frame <'dat> {
+	tag: & 'dat str,
+	position: usize,
+	attr: Attr,
+	link: Option <& 'dat str>,
+}
+
+impl <'dat> Markup <'dat> {
+
+	pub fn parse (source: & 'dat str, colours: & ColoursMap) -> Result <Self, MarkupError> {
+		let mut items = Vec::new ();
+		let mut stack: Vec <Frame> = Vec::new ();
+		let mut attr = Attr::default ();
+		let mut link = None;
+		let mut pos = 0;
+		while pos < source.len () {
+			let rest = & source [pos .. ];
+			if rest.starts_with ("[[") {
+				items.push (Item::Text (Cow::Borrowed ("[")));
+				pos += 2;
+				continue;
+			}
+			if rest.starts_with ("]]") {
+				items.push (Item::Text (Cow::Borrowed ("]")));
+				pos += 2;
+				continue;
+			}
+			if rest.starts_with ('\n') {
+				items.push (Item::Newline);
+				pos += 1;
+				continue;
+			}
+			if ! rest.starts_with ('[') {
+				let end = rest.find (['[', ']', '\n']).unwrap_or (rest.len ());
+				if end == 0 {
+					return Err (MarkupError::new (source, pos, MarkupErrorKind::UnexpectedCloseBracket));
+				}
+				items.push (Item::Text (Cow::Borrowed (& rest [ .. end])));
+				pos += end;
+				continue;
+			}
+			let Some (tag_len) = rest.find (']') else {
+				return Err (MarkupError::new (source, pos, MarkupErrorKind::UnterminatedTag));
+			};
+			let tag = & rest [1 .. tag_len];
+			if let Some (close) = tag.strip_prefix ('/') {
+				let close = close.trim ();
+				let Some (frame) = stack.pop () else {
+					return Err (MarkupError::new (source, pos, MarkupErrorKind::UnexpectedClose));
+				};
+				if ! close.is_empty () && ! frame.tag.split_whitespace ().any (|word| word.split ('=').next () == Some (close)) {
+					return Err (MarkupError::new (source, pos, MarkupErrorKind::MismatchedClose {
+						open: frame.tag.to_owned (),
+						close: close.to_owned (),
+					}));
+				}
+				if link != frame.link {
+					if link.is_some () { items.push (Item::EndLink) }
+					if let Some (url) = frame.link { items.push (Item::BeginLink (Cow::Borrowed (url))) }
+				}
+				attr = frame.attr;
+				link = frame.link;
+				items.push (Item::Attr (attr));
+			} else {
+				let mut new_attr = attr;
+				let mut new_link = link;
+				let mut word_start = None;
+				for (idx, ch) in tag.char_indices ().chain ([ (tag.len (), ' ') ]) {
+					match (word_start, ch.is_whitespace ()) {
+						(None, false) => word_start = Some (idx),
+						(Some (start), true) => {
+							apply_word (& tag [start .. idx], & mut new_attr, & mut new_link, colours)
+								.map_err (|kind| MarkupError::new (source, pos + 1 + start, kind)) ?;
+							word_start = None;
+						},
+						_ => (),
+					}
+				}
+				if tag.trim ().is_empty () {
+					return Err (MarkupError::new (source, pos, MarkupErrorKind::EmptyTag));
+				}
+				stack.push (Frame { tag, position: pos, attr, link });
+				if new_link != link {
+					if link.is_some () { items.push (Item::EndLink) }
+					if let Some (url) = new_link { items.push (Item::BeginLink (Cow::Borrowed (url))) }
+				}
+				attr = new_attr;
+				link = new_link;
+				items.push (Item::Attr (attr));
+			}
+			pos += tag_len + 1;
+		}
+		if let Some (frame) = stack.pop () {
+			return Err (MarkupError::new (source, frame.position, MarkupErrorKind::UnclosedTag (frame.tag.to_owned ())));
+		}
+		Ok (Self { items })
+	}
+
+}
+
+impl <'dat> Printable <'dat> for Markup <'dat> {
+
+	fn print (self, target: & mut impl Target <'dat>) {
+		for item in self.items {
+			match item {
+				Item::Attr (attr) => target.push_attr (attr),
+				Item::Text (text) => target.push_str (text),
+				Item::Newline => target.newline (),
+				Item::BeginLink (url) => target.begin_link (url),
+				Item::EndLink => target.end_link (),
+			}
+		}
+	}
+
+}
+
+fn apply_word <'dat> (
+	word: & 'dat str,
+	attr: & mut Attr,
+	link: & mut Option <& 'dat str>,
+	colours: & ColoursMap,
+) -> Result <(), MarkupErrorKind> {
+	if let Some (new_attr) = attr.modifier (word) {
+		* attr = new_attr;
+		return Ok (());
+	}
+	match word.split_once ('=') {
+		Some (("fg", colour)) => * attr = attr.foreground (lookup_colour (colour, colours) ?),
+		Some (("bg", colour)) => * attr = attr.background (lookup_colour (colour, colours) ?),
+		Some (("ul", colour)) => * attr = attr.underline_colour (lookup_colour (colour, colours) ?),
+		Some (("link", url)) => * link = Some (url),
+		_ => return Err (MarkupErrorKind::UnknownAttribute (word.to_owned ())),
+	}
+	Ok (())
+}
+
+fn lookup_colour (value: & str, colours: & ColoursMap) -> Result <Colour, MarkupErrorKind> {
+	if colour::is_literal (value) {
+		return value.parse ()
+			.map_err (|err| MarkupErrorKind::InvalidColour (value.to_owned (), err));
+	}
+	colours.get (value).copied ()
+		.or_else (|| colour_names::lookup (value))
+		.ok_or_else (|| MarkupErrorKind::UnknownColour (value.to_owned ()))
+}
+
+#[ derive (Debug, Eq, PartialEq, thiserror::Error) ]
+#[ error ("{kind} at line {line}, column {column}") ]
+pub struct MarkupError {
+	pub position: usize,
+	pub line: usize,
+	pub column: usize,
+	#[ source ]
+	pub kind: MarkupErrorKind,
+}
+
+impl MarkupError {
+
+	fn new (source: & str, position: usize, kind: MarkupErrorKind) -> Self {
+		let before = & source [ .. position];
+		let line = before.matches ('\n').count () + 1;
+		let line_start = before.rfind ('\n').map_or (0, |pos| pos + 1);
+		let column = before [line_start .. ].chars ().count () + 1;
+		Self { position, line, column, kind }
+	}
+
+}
+
+#[ derive (Debug, Eq, PartialEq, thiserror::Error) ]
+pub enum MarkupErrorKind {
+	#[ error ("Unterminated tag") ]
+	UnterminatedTag,
+	#[ error ("Empty tag") ]
+	EmptyTag,
+	#[ error ("Unexpected ']'") ]
+	UnexpectedCloseBracket,
+	#[ error ("Close tag without matching open tag") ]
+	UnexpectedClose,
+	#[ error ("Close tag {close} does not match open tag {open}") ]
+	MismatchedClose { open: String, close: String },
+	#[ error ("Tag not closed: {0}") ]
+	UnclosedTag (String),
+	#[ error ("Unknown attribute: {0}") ]
+	UnknownAttribute (String),
+	#[ error ("Unknown colour name: {0}") ]
+	UnknownColour (String),
+	#[ error ("Invalid colour {0}") ]
+	InvalidColour (String, #[ source ] ParseColourError),
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::grid::GridTarget;
+	use crate::plain::PlainTarget;
+
+	fn colours () -> ColoursMap {
+		ColoursMap::build ([
+			("red", "#ff0000"),
+			("warning", "red"),
+		]).unwrap ()
+	}
+
+	#[ test ]
+	fn markup_nesting () -> anyhow::Result <()> {
+		let colours = colours ();
+		let markup = Markup::parse ("a[bold\tfg=warning]b[italic]c[/]d[/bold]e [[x]]\nf", & colours) ?;
+		let mut target = GridTarget::new (80);
+		target.push (markup);
+		assert_eq! ("abcde [x]\nf\n", target.to_text ());
+		assert_eq! (
+			concat! (
+				".ABA.....\n",
+				".\n",
+				"A: fg=#ff0000 bold\n",
+				"B: fg=#ff0000 bold italic\n"),
+			target.to_style_map ());
+		Ok (())
+	}
+
+	#[ test ]
+	fn markup_links () -> anyhow::Result <()> {
+		let colours = colours ();
+		let markup = Markup::parse ("[bold fg=#00f]Disk full[/] on [link=file:///dev/sda underline]sda[/]", & colours) ?;
+		let mut target = PlainTarget::new_fmt (String::new (), 80);
+		target.push (markup);
+		assert_eq! ("Disk full on sda <file:///dev/sda>", target.finish () ?);
+		Ok (())
+	}
+
+	#[ test ]
+	fn markup_errors () {
+		let colours = colours ();
+		let parse = |source| Markup::parse (source, & colours).err ().unwrap ();
+		assert_eq! (
+			MarkupError { position: 6, line: 2, column: 4, kind: MarkupErrorKind::UnknownColour ("bleu".to_owned ()) },
+			parse ("ab\ncd[fg=bleu]x[/]"));
+		assert_eq! (
+			MarkupErrorKind::UnknownAttribute ("wobbly".to_owned ()),
+			parse ("[bold wobbly]x[/]").kind);
+		assert_eq! (
+			MarkupError { position: 10, line: 1, column: 9, kind: MarkupErrorKind::UnknownAttribute ("wobbly".to_owned ()) },
+			parse ("x[bold\t\u{3000}wobbly]x[/]"));
+		assert_eq! (
+			MarkupErrorKind::InvalidColour ("#12".to_owned (), ParseColourError::InvalidLength (3)),
+			parse ("[fg=#12]").kind);
+		assert_eq! (MarkupErrorKind::UnterminatedTag, parse ("x[bold").kind);
+		assert_eq! (MarkupErrorKind::UnexpectedClose, parse ("x[/]").kind);
+		assert_eq! (MarkupErrorKind::UnexpectedCloseBracket, parse ("x]").kind);
+		assert_eq! (MarkupErrorKind::EmptyTag, parse ("[ ]").kind);
+		assert_eq! (
+			MarkupError { position: 1, line: 1, column: 2, kind: MarkupErrorKind::UnclosedTag ("bold".to_owned ()) },
+			parse ("x[bold]y"));
+		assert_eq! (
+			MarkupErrorKind::MismatchedClose { open: "bold".to_owned (), close: "italic".to_owned () },
+			parse ("[bold]y[/italic]").kind);
+		assert_eq! (
+			"Unknown attribute: x at line 1, column 2",
+			parse ("[x]").to_string ());
+	}
+
+}
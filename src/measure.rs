@@ -0,0 +1,81 @@
+use std::borrow::Cow;
+use std::cmp;
+
+use crate::{ Attr, Target };
+use crate::width;
+
+#[ derive (Clone, Copy, Debug, Default, Eq, PartialEq) ]
+pub struct Measurement {
+	pub min_width: usize,
+	pub width: usize,
+	pub height: usize,
+}
+
+impl Measurement {
+
+	#[ inline ]
+	pub const fn new (min_width: usize, width: usize, height: usize) -> Self {
+		Self { min_width, width, height }
+	}
+
+	#[ inline ]
+	pub const fn fixed (width: usize, height: usize) -> Self {
+		Self { min_width: width, width, height }
+	}
+
+}
+
+pub trait Measure {
+	fn measure (& self, max_width: usize) -> Measurement;
+}
+
+pub struct MeasureTarget {
+	width: usize,
+	col: usize,
+	max_col: usize,
+	lines: usize,
+}
+
+impl MeasureTarget {
+
+	#[ inline ]
+	pub fn new (width: usize) -> Self {
+		Self { width, col: 0, max_col: 0, lines: 0 }
+	}
+
+	#[ inline ]
+	pub fn used_width (& self) -> usize {
+		cmp::max (self.max_col, self.col)
+	}
+
+	#[ inline ]
+	pub fn height (& self) -> usize {
+		self.lines + usize::from (self.col > 0)
+	}
+
+}
+
+impl <'dat> Target <'dat> for MeasureTarget {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, _attr: Attr) {
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		self.col += width::str_width (& text);
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.max_col = cmp::max (self.max_col, self.col);
+		self.col = 0;
+		self.lines += 1;
+	}
+
+}
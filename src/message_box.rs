@@ -1,8 +1,11 @@
 use std::borrow::Cow;
 use std::cmp;
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{ Attr, Printable, Target };
 use crate::border_box::BoxStyle;
+use crate::measure::{ Measure, Measurement };
+use crate::width;
 
 pub struct MessageBox <'dat> {
 	box_style: BoxStyle,
@@ -24,13 +27,28 @@ impl <'dat> MessageBox <'dat> {
 
 }
 
+impl <'dat> Measure for MessageBox <'dat> {
+
+	fn measure (& self, max_width: usize) -> Measurement {
+		let (_, min_width) = split (& self.text, 1);
+		let (_, width) = split (& self.text, usize::MAX);
+		let (lines, text_width) = split (& self.text, max_width.saturating_sub (5));
+		Measurement {
+			min_width: min_width + 5,
+			width: cmp::min (width, text_width) + 5,
+			height: lines.len () + 2,
+		}
+	}
+
+}
+
 impl <'dat> Printable <'dat> for MessageBox <'dat> {
 
 	fn print (self, target: & mut impl Target <'dat>) {
 
 		let box_style = self.box_style;
 		let text = & * self.text;
-		let (lines, text_width) = split (text, target.width ());
+		let (lines, text_width) = split (text, target.width ().saturating_sub (5));
 		let border_box = box_style.with_width (text_width + 4);
 
 		target.push (" ");
@@ -44,7 +62,7 @@ impl <'dat> Printable <'dat> for MessageBox <'dat> {
 			let mut buf = String::new ();
 			buf.push_str ("  ");
 			buf.push_str (text);
-			for _ in text.chars ().count () .. text_width {
+			for _ in width::str_width (text) .. text_width {
 				buf.push (' ');
 			}
 			buf.push_str ("  ");
@@ -75,7 +93,7 @@ impl <'dat> MiniMessageBox <'dat> {
 		text: impl Into <Cow <'dat, str>>,
 	) -> Self {
 		let text = text.into ();
-		let width = text.chars ().count () + 4;
+		let width = width::str_width (& text) + 4;
 		Self { box_style, text, width }
 	}
 
@@ -86,6 +104,15 @@ impl <'dat> MiniMessageBox <'dat> {
 
 }
 
+impl <'dat> Measure for MiniMessageBox <'dat> {
+
+	#[ inline ]
+	fn measure (& self, _max_width: usize) -> Measurement {
+		Measurement::fixed (self.width, 1)
+	}
+
+}
+
 impl <'dat> Printable <'dat> for MiniMessageBox <'dat> {
 
 	fn print (self, target: & mut impl Target <'dat>) {
@@ -105,39 +132,98 @@ impl <'dat> Printable <'dat> for MiniMessageBox <'dat> {
 
 fn split (text: & str, max_width: usize) -> (Vec <(usize, usize)>, usize) {
 	let mut lines = Vec::new ();
-	let text_trim = text.trim_end ();
-	let mut chars = text_trim.char_indices ();
-	let mut line_pos = 0;
-	let mut line_chars = 0;
-	let mut prev_pos = 0;
 	let mut text_width = 0;
-	loop {
-		match chars.next () {
-			Some ((pos, '\n')) => {
-				lines.push ((line_pos, pos));
-				line_pos = pos + 1;
-				text_width = cmp::max (text_width, line_chars);
-				line_chars = 0;
-				prev_pos = pos;
-			},
-			Some ((pos, _)) => {
-				if line_chars == max_width {
-					lines.push ((line_pos, prev_pos));
-					line_pos = prev_pos;
-					text_width = cmp::max (text_width, line_chars);
-					line_chars = 0;
-				}
-				line_chars += 1;
-				prev_pos = pos;
-			},
-			None => {
-				if line_pos < text_trim.chars ().count () {
-					lines.push ((line_pos, text_trim.chars ().count ()));
-					text_width = cmp::max (text_width, line_chars);
-				}
-				break;
-			},
+	let mut line_pos = 0;
+	let text = text.trim_end ();
+	if text.is_empty () { return (lines, text_width) }
+	for line in text.split ('\n') {
+		let mut rest = line;
+		loop {
+			let (mut split_pos, mut line_width) = width::split_at_width (rest, max_width);
+			if split_pos == 0 && ! rest.is_empty () {
+				let grapheme = rest.graphemes (true).next ().unwrap ();
+				split_pos = grapheme.len ();
+				line_width = width::grapheme_width (grapheme);
+			}
+			let start = line_pos + (line.len () - rest.len ());
+			lines.push ((start, start + split_pos));
+			text_width = cmp::max (text_width, line_width);
+			rest = & rest [split_pos .. ];
+			if rest.is_empty () { break }
 		}
+		line_pos += line.len () + 1;
 	}
 	(lines, text_width)
 }
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::border_box::TEST_BOX_STYLE;
+	use crate::grid::GridTarget;
+	use crate::measure::MeasureTarget;
+
+	#[ test ]
+	fn message_box_snapshot () {
+		let mut target = GridTarget::new (13);
+		target.push (MessageBox::new (TEST_BOX_STYLE, "Hello world\nok"));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅▅▅▅▅▅▅\n",
+				"   Hello wo  \n",
+				"   rld       \n",
+				"   ok        \n",
+				" 🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄\n"),
+			target.to_text ());
+		assert_eq! (13, target.used_width ());
+		assert_eq! (
+			concat! (
+				".AAAAAAAAAAAA\n",
+				".BBBBBBBBBBBB\n",
+				".BBBBBBBBBBBB\n",
+				".BBBBBBBBBBBB\n",
+				".AAAAAAAAAAAA\n",
+				"A: fg=#504030 bg=#000000\n",
+				"B: fg=#ffffff bg=#504030\n"),
+			target.to_style_map ());
+	}
+
+	#[ test ]
+	fn message_box_measure () {
+		let message_box = MessageBox::new (TEST_BOX_STYLE, "Hello world\n日本");
+		assert_eq! (Measurement::new (7, 16, 4), message_box.measure (80));
+		assert_eq! (Measurement::new (7, 8, 8), message_box.measure (8));
+		for max_width in 7 .. 20 {
+			assert! (message_box.measure (max_width).width <= max_width);
+		}
+		let mut target = MeasureTarget::new (8);
+		target.push (MessageBox::new (TEST_BOX_STYLE, "Hello world\n日本"));
+		assert_eq! ((8, 8), (target.used_width (), target.height ()));
+		assert_eq! (Measurement::fixed (6, 1), MiniMessageBox::new (TEST_BOX_STYLE, "hi").measure (80));
+	}
+
+	#[ test ]
+	fn mini_message_box_snapshot () {
+		let mut target = GridTarget::new (20);
+		target.push (MiniMessageBox::new (TEST_BOX_STYLE, "hi"));
+		assert_eq! ("🬫 hi 🬛\n", target.to_text ());
+		assert_eq! (
+			concat! (
+				"ABBBBA\n",
+				"A: fg=#504030 bg=#000000\n",
+				"B: fg=#ffffff bg=#504030\n"),
+			target.to_style_map ());
+	}
+
+	#[ test ]
+	fn split_wide () {
+		assert_eq! ((vec! [ (0, 5), (6, 11) ], 5), split ("hello\nworld\n", 80));
+		assert_eq! ((vec! [ (0, 3), (3, 5) ], 3), split ("hello", 3));
+		assert_eq! ((vec! [ (0, 6), (6, 9) ], 4), split ("日本語", 5));
+		assert_eq! ((vec! [ (0, 4), (4, 5) ], 2), split ("e\u{301}xy", 2));
+		assert_eq! ((vec! [ (0, 3), (3, 6) ], 2), split ("日本", 1));
+		assert_eq! ((vec! [], 0), split ("", 10));
+	}
+
+}
@@ -0,0 +1,80 @@
+use std::fmt;
+use std::io;
+
+pub trait Output {
+
+	type Inner;
+
+	fn write_str (& mut self, text: & str) -> io::Result <()>;
+	fn flush (& mut self) -> io::Result <()>;
+	fn into_inner (self) -> Self::Inner;
+
+}
+
+pub struct IoOutput <Out: io::Write> {
+	out: Out,
+}
+
+impl <Out: io::Write> IoOutput <Out> {
+
+	#[ inline ]
+	pub fn new (out: Out) -> Self {
+		Self { out }
+	}
+
+}
+
+impl <Out: io::Write> Output for IoOutput <Out> {
+
+	type Inner = Out;
+
+	#[ inline ]
+	fn write_str (& mut self, text: & str) -> io::Result <()> {
+		self.out.write_all (text.as_bytes ())
+	}
+
+	#[ inline ]
+	fn flush (& mut self) -> io::Result <()> {
+		self.out.flush ()
+	}
+
+	#[ inline ]
+	fn into_inner (self) -> Out {
+		self.out
+	}
+
+}
+
+pub struct FmtOutput <Out: fmt::Write> {
+	out: Out,
+}
+
+impl <Out: fmt::Write> FmtOutput <Out> {
+
+	#[ inline ]
+	pub fn new (out: Out) -> Self {
+		Self { out }
+	}
+
+}
+
+impl <Out: fmt::Write> Output for FmtOutput <Out> {
+
+	type Inner = Out;
+
+	#[ inline ]
+	fn write_str (& mut self, text: & str) -> io::Result <()> {
+		self.out.write_str (text).map_err (io::Error::other)
+	}
+
+	#[ inline ]
+	fn flush (& mut self) -> io::Result <()> {
+		Ok (())
+	}
+
+	#[ inline ]
+	fn into_inner (self) -> Out {
+		self.out
+	}
+
+}
@@ -0,0 +1,259 @@
+use std::borrow::Cow;
+use std::env;
+use std::fmt;
+use std::io::{ self, IsTerminal };
+use std::mem;
+
+use crate::{ Attr, Target };
+use crate::ansi::{ AnsiTarget, ColourDepth };
+use crate::output::{ FmtOutput, IoOutput, Output };
+
+pub struct PlainTarget <Out: Output> {
+	out: Out,
+	width: usize,
+	link_urls: bool,
+	link: Option <(String, String)>,
+	link_urls_pending: Vec <String>,
+	error: Option <io::Error>,
+}
+
+impl <Out: io::Write> PlainTarget <IoOutput <Out>> {
+
+	#[ inline ]
+	pub fn new (out: Out, width: usize) -> Self {
+		Self::with_output (IoOutput::new (out), width)
+	}
+
+}
+
+impl <Out: fmt::Write> PlainTarget <FmtOutput <Out>> {
+
+	#[ inline ]
+	pub fn new_fmt (out: Out, width: usize) -> Self {
+		Self::with_output (FmtOutput::new (out), width)
+	}
+
+}
+
+impl <Out: Output> PlainTarget <Out> {
+
+	#[ inline ]
+	pub fn with_output (out: Out, width: usize) -> Self {
+		Self {
+			out,
+			width,
+			link_urls: true,
+			link: None,
+			link_urls_pending: Vec::new (),
+			error: None,
+		}
+	}
+
+	#[ inline ]
+	pub fn with_link_urls (mut self, link_urls: bool) -> Self {
+		self.link_urls = link_urls;
+		self
+	}
+
+	#[ inline ]
+	pub fn error (& self) -> Option <& io::Error> {
+		self.error.as_ref ()
+	}
+
+	pub fn finish (mut self) -> io::Result <Out::Inner> {
+		self.write_link_urls ();
+		if let Some (err) = self.error.take () { return Err (err) }
+		self.out.flush () ?;
+		Ok (self.out.into_inner ())
+	}
+
+	fn write_link_urls (& mut self) {
+		for url in mem::take (& mut self.link_urls_pending) {
+			self.write (" <");
+			self.write (& url);
+			self.write (">");
+		}
+	}
+
+	fn write (& mut self, text: & str) {
+		if self.error.is_some () { return }
+		if let Err (err) = self.out.write_str (text) {
+			self.error = Some (err);
+		}
+	}
+
+}
+
+impl <'dat, Out: Output> Target <'dat> for PlainTarget <Out> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, _attr: Attr) {
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		if let Some ((_, link_text)) = self.link.as_mut () { link_text.push_str (& text) }
+		self.write (& text);
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.write_link_urls ();
+		self.write ("\n");
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		if ! self.link_urls { return }
+		self.link = Some ((url.into_owned (), String::new ()));
+	}
+
+	fn end_link (& mut self) {
+		let Some ((url, text)) = self.link.take () else { return };
+		if text.trim () == url { return }
+		self.link_urls_pending.push (url);
+	}
+
+}
+
+pub enum AutoTarget <Out: io::Write> {
+	Plain (PlainTarget <IoOutput <Out>>),
+	Ansi (AnsiTarget <Out>),
+}
+
+impl AutoTarget <io::Stdout> {
+
+	pub fn stdout () -> Self {
+		let out = io::stdout ();
+		let force = env::var ("CLICOLOR_FORCE").is_ok_and (|value| ! value.is_empty () && value != "0");
+		if out.is_terminal () {
+			let width = termsize::get ().map (|size| size.cols as usize).unwrap_or (80);
+			Self::Ansi (AnsiTarget::new (out, width).with_colour_depth (ColourDepth::detect ()))
+		} else if force {
+			Self::Ansi (AnsiTarget::new (out, 80).with_colour_depth (ColourDepth::detect ()))
+		} else {
+			Self::Plain (PlainTarget::new (out, 80))
+		}
+	}
+
+}
+
+impl <Out: io::Write> AutoTarget <Out> {
+
+	#[ inline ]
+	pub fn is_ansi (& self) -> bool {
+		matches! (self, Self::Ansi (_))
+	}
+
+	#[ inline ]
+	pub fn finish (self) -> io::Result <Out> {
+		match self {
+			Self::Plain (target) => target.finish (),
+			Self::Ansi (target) => target.finish (),
+		}
+	}
+
+}
+
+impl <'dat, Out: io::Write> Target <'dat> for AutoTarget <Out> {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		match self {
+			Self::Plain (target) => target.width (),
+			Self::Ansi (target) => target.width (),
+		}
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		match self {
+			Self::Plain (target) => target.push_attr (attr),
+			Self::Ansi (target) => target.push_attr (attr),
+		}
+	}
+
+	#[ inline ]
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		match self {
+			Self::Plain (target) => target.push_str (text),
+			Self::Ansi (target) => target.push_str (text),
+		}
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		match self {
+			Self::Plain (target) => target.newline (),
+			Self::Ansi (target) => target.newline (),
+		}
+	}
+
+	#[ inline ]
+	fn begin_link (& mut self, url: Cow <'dat, str>) {
+		match self {
+			Self::Plain (target) => target.begin_link (url),
+			Self::Ansi (target) => target.begin_link (url),
+		}
+	}
+
+	#[ inline ]
+	fn end_link (& mut self) {
+		match self {
+			Self::Plain (target) => target.end_link (),
+			Self::Ansi (target) => target.end_link (),
+		}
+	}
+
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::{ Colour, Link };
+
+	#[ test ]
+	fn plain_fmt () {
+		let mut target = PlainTarget::new_fmt (String::new (), 80);
+		target.push (Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00)));
+		target.push ("hello");
+		target.push (Attr::default ());
+		target.newline ();
+		target.push ("world".to_owned ());
+		assert_eq! ("hello\nworld", target.finish ().unwrap ());
+	}
+
+	#[ test ]
+	fn plain_links () {
+		let mut target = PlainTarget::new_fmt (String::new (), 80);
+		target.push (Link::new ("https://example.com/", "example"));
+		target.push (" ");
+		target.push (Link::new ("https://example.com/", "https://example.com/"));
+		target.push (" and ");
+		target.push (Link::new ("https://example.org/", "another"));
+		target.newline ();
+		target.push (Link::new ("https://example.net/", "last"));
+		assert_eq! (
+			"example https://example.com/ and another <https://example.com/> <https://example.org/>\n\
+				last <https://example.net/>",
+			target.finish ().unwrap ());
+		let mut target = PlainTarget::new_fmt (String::new (), 80).with_link_urls (false);
+		target.push (Link::new ("https://example.com/", "example"));
+		assert_eq! ("example", target.finish ().unwrap ());
+	}
+
+	#[ test ]
+	fn plain_io () {
+		let mut target = PlainTarget::new (Vec::new (), 80);
+		target.push ("hello");
+		target.newline ();
+		assert_eq! (b"hello\n", target.finish ().unwrap ().as_slice ());
+	}
+
+}
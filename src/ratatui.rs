@@ -7,7 +7,7 @@ use std::borrow::Cow;
 use std::io::Write;
 use std::mem;
 
-use crate::{ Attr, Colour, Target };
+use crate::{ AnsiColour, Attr, Colour, Target, TermColour, Underline };
 
 pub struct RatTerm <Out: Write> {
 	term: Option <ratatui::Terminal <rat_back::CrosstermBackend <Out>>>,
@@ -77,16 +77,61 @@ impl From <Attr> for rat_style::Style {
 		} else {
 			style = style.bg (rat_style::Color::Reset);
 		}
-		if attr.bold {
-			style = style.add_modifier (rat_style::Modifier::BOLD);
+		if let Some (underline_colour) = attr.underline_colour {
+			style = style.underline_color (underline_colour.into ());
 		} else {
-			style = style.remove_modifier (rat_style::Modifier::BOLD);
+			style = style.underline_color (rat_style::Color::Reset);
+		}
+		for (enabled, modifier) in [
+			(attr.bold, rat_style::Modifier::BOLD),
+			(attr.dim, rat_style::Modifier::DIM),
+			(attr.italic, rat_style::Modifier::ITALIC),
+			(attr.underline != Underline::None, rat_style::Modifier::UNDERLINED),
+			(attr.blink, rat_style::Modifier::SLOW_BLINK),
+			(attr.reverse, rat_style::Modifier::REVERSED),
+			(attr.hidden, rat_style::Modifier::HIDDEN),
+			(attr.strikethrough, rat_style::Modifier::CROSSED_OUT),
+		] {
+			if enabled {
+				style = style.add_modifier (modifier);
+			} else {
+				style = style.remove_modifier (modifier);
+			}
 		}
 		style
 	}
 
 }
 
+impl From <TermColour> for rat_style::Color {
+
+	fn from (colour: TermColour) -> Self {
+		match colour {
+			TermColour::Ansi (ansi) => match ansi {
+				AnsiColour::Black => Self::Black,
+				AnsiColour::Red => Self::Red,
+				AnsiColour::Green => Self::Green,
+				AnsiColour::Yellow => Self::Yellow,
+				AnsiColour::Blue => Self::Blue,
+				AnsiColour::Magenta => Self::Magenta,
+				AnsiColour::Cyan => Self::Cyan,
+				AnsiColour::White => Self::Gray,
+				AnsiColour::BrightBlack => Self::DarkGray,
+				AnsiColour::BrightRed => Self::LightRed,
+				AnsiColour::BrightGreen => Self::LightGreen,
+				AnsiColour::BrightYellow => Self::LightYellow,
+				AnsiColour::BrightBlue => Self::LightBlue,
+				AnsiColour::BrightMagenta => Self::LightMagenta,
+				AnsiColour::BrightCyan => Self::LightCyan,
+				AnsiColour::BrightWhite => Self::White,
+			},
+			TermColour::Indexed (index) => Self::Indexed (index),
+			TermColour::Rgb (colour) => colour.into (),
+		}
+	}
+
+}
+
 impl From <Colour> for rat_style::Color {
 
 	#[ inline ]
@@ -0,0 +1,278 @@
+use std::borrow::Cow;
+use std::cmp;
+use std::fmt::Write as _;
+use std::mem;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{ Attr, Colour, Target, TermColour, Underline };
+use crate::html::escape;
+use crate::width;
+
+const CELL_WIDTH: f32 = 9.0;
+const CELL_HEIGHT: f32 = 18.0;
+const FONT_SIZE: f32 = 15.0;
+const PADDING: f32 = 12.0;
+const TITLE_HEIGHT: f32 = 28.0;
+
+pub struct SvgTarget {
+	width: usize,
+	lines: Vec <Vec <SvgCell>>,
+	cells: Vec <SvgCell>,
+	col: usize,
+	attr: Attr,
+	title: Option <String>,
+	foreground: Colour,
+	background: Colour,
+}
+
+struct SvgCell {
+	col: usize,
+	width: usize,
+	text: String,
+	attr: Attr,
+}
+
+impl SvgTarget {
+
+	#[ inline ]
+	pub fn new (width: usize) -> Self {
+		Self {
+			width,
+			lines: Vec::new (),
+			cells: Vec::new (),
+			col: 0,
+			attr: Attr::default (),
+			title: None,
+			foreground: Colour::new (0xd0, 0xd0, 0xd0),
+			background: Colour::new (0x1e, 0x1e, 0x1e),
+		}
+	}
+
+	#[ inline ]
+	pub fn with_title (mut self, title: impl Into <String>) -> Self {
+		self.title = Some (title.into ());
+		self
+	}
+
+	#[ inline ]
+	pub fn with_colours (mut self, foreground: Colour, background: Colour) -> Self {
+		self.foreground = foreground;
+		self.background = background;
+		self
+	}
+
+	pub fn build (mut self) -> String {
+		if ! self.cells.is_empty () {
+			self.lines.push (mem::take (& mut self.cells));
+		}
+		let cols = self.lines.iter ()
+			.filter_map (|line| line.last ())
+			.map (|cell| cell.col + cell.width)
+			.fold (self.width, cmp::max);
+		let grid_width = cols as f32 * CELL_WIDTH;
+		let grid_height = self.lines.len () as f32 * CELL_HEIGHT;
+		let total_width = grid_width + PADDING * 2.0;
+		let total_height = grid_height + PADDING * 2.0 + TITLE_HEIGHT;
+		let mut svg = String::new ();
+		writeln! (svg,
+			"<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{total_width}\" height=\"{total_height}\" \
+				viewBox=\"0 0 {total_width} {total_height}\">").unwrap ();
+		writeln! (svg,
+			"<rect width=\"{total_width}\" height=\"{total_height}\" rx=\"8\" fill=\"{}\"/>",
+			self.background).unwrap ();
+		for (idx, colour) in [ "#ff5f57", "#febc2e", "#28c840" ].iter ().enumerate () {
+			writeln! (svg,
+				"<circle cx=\"{}\" cy=\"{}\" r=\"6\" fill=\"{colour}\"/>",
+				PADDING + 6.0 + idx as f32 * 20.0,
+				TITLE_HEIGHT / 2.0 + 2.0).unwrap ();
+		}
+		if let Some (title) = & self.title {
+			writeln! (svg,
+				"<text x=\"{}\" y=\"{}\" fill=\"{}\" font-family=\"sans-serif\" font-size=\"13\" \
+					text-anchor=\"middle\">{}</text>",
+				total_width / 2.0,
+				TITLE_HEIGHT / 2.0 + 6.0,
+				self.foreground,
+				escape (title)).unwrap ();
+		}
+		writeln! (svg,
+			"<g transform=\"translate({PADDING} {})\" font-family=\"monospace\" font-size=\"{FONT_SIZE}\" \
+				xml:space=\"preserve\">",
+			PADDING + TITLE_HEIGHT).unwrap ();
+		for (row, line) in self.lines.iter ().enumerate () {
+			for cell in line {
+				self.write_cell (& mut svg, row, cell);
+			}
+		}
+		writeln! (svg, "</g>").unwrap ();
+		writeln! (svg, "</svg>").unwrap ();
+		svg
+	}
+
+	fn write_cell (& self, svg: & mut String, row: usize, cell: & SvgCell) {
+		let attr = cell.attr;
+		let attr_foreground = attr.foreground.map (TermColour::to_rgb);
+		let attr_background = attr.background.map (TermColour::to_rgb);
+		let (foreground, background) = if attr.reverse {
+			(attr_background.or (Some (self.background)), attr_foreground.or (Some (self.foreground)))
+		} else {
+			(attr_foreground, attr_background)
+		};
+		let foreground = foreground.unwrap_or (self.foreground);
+		let x = cell.col as f32 * CELL_WIDTH;
+		let y = row as f32 * CELL_HEIGHT;
+		let width = cell.width as f32 * CELL_WIDTH;
+		if let Some (background) = background {
+			writeln! (svg,
+				"<rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{CELL_HEIGHT}\" fill=\"{}\"/>",
+				background).unwrap ();
+		}
+		if attr.hidden || cell.text.trim ().is_empty () { return }
+		let opacity = if attr.dim { " opacity=\"0.5\"" } else { "" };
+		if let Some ((top, bottom)) = block_extent (& cell.text) {
+			writeln! (svg,
+				"<rect x=\"{x}\" y=\"{}\" width=\"{width}\" height=\"{}\" fill=\"{}\"{opacity}/>",
+				y + top * CELL_HEIGHT,
+				(bottom - top) * CELL_HEIGHT,
+				foreground).unwrap ();
+			return;
+		}
+		if let Some (mask) = sextant_mask (& cell.text) {
+			for sextant_row in 0 .. 3 {
+				let (left, right) = (mask >> (sextant_row * 2) & 1 != 0, mask >> (sextant_row * 2 + 1) & 1 != 0);
+				if ! left && ! right { continue }
+				let rect_x = if left { x } else { x + width / 2.0 };
+				let rect_width = if left && right { width } else { width / 2.0 };
+				writeln! (svg,
+					"<rect x=\"{rect_x}\" y=\"{}\" width=\"{rect_width}\" height=\"{}\" fill=\"{}\"{opacity}/>",
+					y + sextant_row as f32 * CELL_HEIGHT / 3.0,
+					CELL_HEIGHT / 3.0,
+					foreground).unwrap ();
+			}
+			return;
+		}
+		let mut extra = String::from (opacity);
+		if attr.bold { extra.push_str (" font-weight=\"bold\"") }
+		if attr.italic { extra.push_str (" font-style=\"italic\"") }
+		let mut decoration = Vec::new ();
+		if attr.underline != Underline::None { decoration.push ("underline") }
+		if attr.strikethrough { decoration.push ("line-through") }
+		if ! decoration.is_empty () {
+			write! (extra, " text-decoration=\"{}\"", decoration.join (" ")).unwrap ();
+		}
+		writeln! (svg,
+			"<text x=\"{x}\" y=\"{}\" fill=\"{}\"{extra}>{}</text>",
+			y + CELL_HEIGHT * 0.75,
+			foreground,
+			escape (& cell.text)).unwrap ();
+	}
+
+}
+
+impl <'dat> Target <'dat> for SvgTarget {
+
+	#[ inline ]
+	fn width (& self) -> usize {
+		self.width
+	}
+
+	#[ inline ]
+	fn push_attr (& mut self, attr: Attr) {
+		self.attr = attr;
+	}
+
+	fn push_str (& mut self, text: Cow <'dat, str>) {
+		for grapheme in text.graphemes (true) {
+			let width = width::grapheme_width (grapheme);
+			if width == 0 {
+				if let Some (cell) = self.cells.last_mut () { cell.text.push_str (grapheme) }
+				continue;
+			}
+			self.cells.push (SvgCell {
+				col: self.col,
+				width,
+				text: grapheme.to_owned (),
+				attr: self.attr,
+			});
+			self.col += width;
+		}
+	}
+
+	#[ inline ]
+	fn newline (& mut self) {
+		self.lines.push (mem::take (& mut self.cells));
+		self.col = 0;
+	}
+
+}
+
+fn block_extent (text: & str) -> Option <(f32, f32)> {
+	let mut chars = text.chars ();
+	let ch = chars.next () ?;
+	if chars.next ().is_some () { return None }
+	match ch as u32 {
+		0x2580 => Some ((0.0, 0.5)),
+		code @ 0x2581 ..= 0x2588 => Some ((1.0 - (code - 0x2580) as f32 / 8.0, 1.0)),
+		0x2594 => Some ((0.0, 0.125)),
+		0x1fb82 => Some ((0.0, 0.25)),
+		0x1fb83 => Some ((0.0, 0.375)),
+		0x1fb84 => Some ((0.0, 0.625)),
+		0x1fb85 => Some ((0.0, 0.75)),
+		0x1fb86 => Some ((0.0, 0.875)),
+		0x2500 => Some ((0.47, 0.53)),
+		0x2501 => Some ((0.44, 0.56)),
+		_ => None,
+	}
+}
+
+fn sextant_mask (text: & str) -> Option <u8> {
+	let mut chars = text.chars ();
+	let ch = chars.next () ?;
+	if chars.next ().is_some () { return None }
+	let index = (ch as u32).checked_sub (0x1fb00).filter (|& index| index < 60) ? as u8;
+	let mask = index + 1;
+	let mask = if mask >= 21 { mask + 1 } else { mask };
+	let mask = if mask >= 42 { mask + 1 } else { mask };
+	Some (mask)
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+
+	#[ test ]
+	fn block_extents () {
+		assert_eq! (Some ((0.375, 1.0)), block_extent ("▅"));
+		assert_eq! (Some ((0.0, 0.625)), block_extent ("🮄"));
+		assert_eq! (Some ((0.0, 1.0)), block_extent ("█"));
+		assert_eq! (None, block_extent ("a"));
+		assert_eq! (Some (0b000001), sextant_mask ("🬀"));
+		assert_eq! (Some (0b011101), sextant_mask ("🬛"));
+		assert_eq! (Some (0b101110), sextant_mask ("🬫"));
+		assert_eq! (Some (0b111110), sextant_mask ("🬻"));
+		assert_eq! (None, sextant_mask ("🬼"));
+		assert_eq! (None, sextant_mask ("a"));
+	}
+
+	#[ test ]
+	fn svg_cells () {
+		let mut target = SvgTarget::new (4).with_title ("a & b");
+		target.push (Attr::default ().background (Colour::new (0x11, 0x22, 0x33)));
+		target.push ("日x");
+		target.newline ();
+		target.push (Attr::default ().foreground (Colour::new (0xff, 0x00, 0x00)));
+		target.push ("▅🬫");
+		let svg = target.build ();
+		assert! (svg.contains (">a &amp; b</text>"));
+		assert! (svg.contains ("<rect x=\"0\" y=\"0\" width=\"18\" height=\"18\" fill=\"#112233\"/>"));
+		assert! (svg.contains ("<text x=\"0\" y=\"13.5\" fill=\"#d0d0d0\">日</text>"));
+		assert! (svg.contains ("<text x=\"18\" y=\"13.5\" fill=\"#d0d0d0\">x</text>"));
+		assert! (svg.contains ("<rect x=\"0\" y=\"24.75\" width=\"9\" height=\"11.25\" fill=\"#ff0000\"/>"));
+		assert! (svg.contains ("<rect x=\"13.5\" y=\"18\" width=\"4.5\" height=\"6\" fill=\"#ff0000\"/>"));
+		assert! (svg.contains ("<rect x=\"9\" y=\"24\" width=\"9\" height=\"6\" fill=\"#ff0000\"/>"));
+		assert! (svg.contains ("<rect x=\"13.5\" y=\"30\" width=\"4.5\" height=\"6\" fill=\"#ff0000\"/>"));
+		assert! (! svg.contains ("🬫"));
+	}
+
+}
@@ -1,9 +1,13 @@
 use std::cmp;
 use std::fmt::{ self, Display };
+use std::iter;
+use std::mem;
 use std::rc::Rc;
 
-use crate::{ Attr, Printable, Target };
+use crate::{ Attr, Link, Printable, Target };
+use crate::width;
 use crate::border_box::BoxStyle;
+use crate::measure::{ Measure, Measurement };
 
 #[ derive (Clone, Copy, Debug) ]
 pub enum Align {
@@ -27,6 +31,7 @@ impl Table {
 		}
 	}
 
+	#[ allow (clippy::match_ref_pats, clippy::needless_borrowed_reference) ]
 	pub fn rows (& self) -> impl Iterator <Item = TableRow> + '_ {
 		self.data.iter ()
 			.map (|row| match row {
@@ -45,6 +50,15 @@ impl Table {
 
 }
 
+impl Measure for Table {
+
+	#[ inline ]
+	fn measure (& self, _max_width: usize) -> Measurement {
+		Measurement::fixed (self.width as usize, self.data.len ())
+	}
+
+}
+
 #[ derive (Clone, Debug) ]
 pub enum TableRow {
 	Cells {
@@ -71,19 +85,13 @@ impl TableRow {
 
 impl Display for TableRow {
 
+	#[ allow (clippy::match_ref_pats, clippy::needless_borrowed_reference) ]
 	fn fmt (& self, fmtr: & mut fmt::Formatter) -> fmt::Result {
 		match self {
 			& TableRow::Cells { ref data, ref widths } => {
-				let mut start = 0;
-				for cell in & ** data {
-					let end = start + cell.span as usize;
-					let width = widths [start .. end].iter ().sum::<u16> () as usize;
-					match cell.align {
-						Align::Left => write! (fmtr, "{:<width$}", cell.text) ?,
-						Align::Centre => write! (fmtr, "{:^width$}", cell.text) ?,
-						Align::Right => write! (fmtr, "{:>width$}", cell.text) ?,
-					}
-					start = end;
+				for (cell, width) in iter::zip (& ** data, cell_widths (data, widths)) {
+					let (before, after) = cell.padding (width);
+					write! (fmtr, "{:before$}{}{:after$}", "", cell.text, "") ?;
 				}
 			},
 			& TableRow::Separator => {
@@ -95,6 +103,43 @@ impl Display for TableRow {
 
 }
 
+impl <'dat> Printable <'dat> for & TableRow {
+
+	#[ allow (clippy::match_ref_pats, clippy::needless_borrowed_reference) ]
+	fn print (self, target: & mut impl Target <'dat>) {
+		match self {
+			& TableRow::Cells { ref data, ref widths } => {
+				let mut buf = String::new ();
+				for (cell, width) in iter::zip (& ** data, cell_widths (data, widths)) {
+					let (before, after) = cell.padding (width);
+					buf.extend (iter::repeat_n (' ', before));
+					if let Some (link) = cell.link.as_ref () {
+						target.push (mem::take (& mut buf));
+						target.push (Link::new (link.clone (), cell.text.clone ()));
+					} else {
+						buf.push_str (& cell.text);
+					}
+					buf.extend (iter::repeat_n (' ', after));
+				}
+				target.push (buf);
+			},
+			& TableRow::Separator => {
+				target.push ("---");
+			},
+		}
+	}
+
+}
+
+fn cell_widths <'a> (data: & 'a [Cell], widths: & 'a [u16]) -> impl Iterator <Item = usize> + 'a {
+	data.iter ().scan (0, |start, cell| {
+		let end = * start + cell.span as usize;
+		let width = widths [* start .. end].iter ().sum::<u16> () as usize;
+		* start = end;
+		Some (width)
+	})
+}
+
 #[ derive (Clone, Debug) ]
 pub struct TableBuilder {
 	data: Vec <Row>,
@@ -126,6 +171,7 @@ impl TableBuilder {
 		}
 	}
 
+	#[ allow (clippy::needless_borrowed_reference) ]
 	fn calc_widths (& self) -> Vec <u16> {
 		let num_cols: u16 =
 			self.data.iter ()
@@ -184,7 +230,19 @@ impl <'tab> RowBuilder <'tab> {
 		width: u16,
 		text: impl Into <String>,
 	) -> & mut Self {
-		self.cell_real (span, align, width, text.into ())
+		self.cell_real (span, align, width, text.into (), None)
+	}
+
+	#[ inline ]
+	pub fn cell_link (
+		& mut self,
+		span: u16,
+		align: Align,
+		width: u16,
+		text: impl Into <String>,
+		url: impl Into <String>,
+	) -> & mut Self {
+		self.cell_real (span, align, width, text.into (), Some (url.into ()))
 	}
 
 	fn cell_real (
@@ -193,9 +251,10 @@ impl <'tab> RowBuilder <'tab> {
 		align: Align,
 		width: u16,
 		text: String,
+		link: Option <String>,
 	) -> & mut Self {
-		let width = cmp::max (width, text.chars ().count ().try_into ().unwrap ());
-		self.data.push (Cell { span, align, width, text });
+		let width = cmp::max (width, width::str_width (& text).try_into ().unwrap ());
+		self.data.push (Cell { span, align, width, text, link });
 		self
 	}
 
@@ -204,6 +263,11 @@ impl <'tab> RowBuilder <'tab> {
 		self.cell (1, Align::Left, 0, text)
 	}
 
+	#[ inline ]
+	pub fn left_link (& mut self, text: impl Into <String>, url: impl Into <String>) -> & mut Self {
+		self.cell_link (1, Align::Left, 0, text, url)
+	}
+
 	#[ inline ]
 	pub fn left_span (& mut self, span: u16, text: impl Into <String>) -> & mut Self {
 		self.cell (span, Align::Left, 0, text)
@@ -214,6 +278,11 @@ impl <'tab> RowBuilder <'tab> {
 		self.cell (1, Align::Centre, 0, text)
 	}
 
+	#[ inline ]
+	pub fn centre_link (& mut self, text: impl Into <String>, url: impl Into <String>) -> & mut Self {
+		self.cell_link (1, Align::Centre, 0, text, url)
+	}
+
 	#[ inline ]
 	pub fn centre_span (& mut self, span: u16, text: impl Into <String>) -> & mut Self {
 		self.cell (span, Align::Centre, 0, text)
@@ -224,6 +293,11 @@ impl <'tab> RowBuilder <'tab> {
 		self.cell (1, Align::Right, 0, text)
 	}
 
+	#[ inline ]
+	pub fn right_link (& mut self, text: impl Into <String>, url: impl Into <String>) -> & mut Self {
+		self.cell_link (1, Align::Right, 0, text, url)
+	}
+
 	#[ inline ]
 	pub fn right_span (& mut self, span: u16, text: String) -> & mut Self {
 		self.cell (span, Align::Right, 0, text)
@@ -263,6 +337,20 @@ pub struct Cell {
 	align: Align,
 	width: u16,
 	text: String,
+	link: Option <String>,
+}
+
+impl Cell {
+
+	fn padding (& self, width: usize) -> (usize, usize) {
+		let padding = width.saturating_sub (width::str_width (& self.text));
+		match self.align {
+			Align::Left => (0, padding),
+			Align::Centre => (padding / 2, padding - padding / 2),
+			Align::Right => (padding, 0),
+		}
+	}
+
 }
 
 pub struct TableBox <'tab> {
@@ -279,6 +367,16 @@ impl <'tab> TableBox <'tab> {
 
 }
 
+impl <'tab> Measure for TableBox <'tab> {
+
+	#[ inline ]
+	fn measure (& self, max_width: usize) -> Measurement {
+		let table = self.table.measure (max_width);
+		Measurement::fixed (table.width + 5, table.height + 2)
+	}
+
+}
+
 impl <'tab, 'dat> Printable <'dat> for TableBox <'tab> {
 
 	fn print (self, target: & mut impl Target <'dat>) {
@@ -299,7 +397,7 @@ impl <'tab, 'dat> Printable <'dat> for TableBox <'tab> {
 				target.push (" ");
 				target.push (self.box_style.text_attr ());
 				target.push ("  ");
-				target.push (row.to_string ());
+				target.push (& row);
 				target.push ("  ");
 				target.push (Attr::default ());
 				target.newline ();
@@ -313,3 +411,86 @@ impl <'tab, 'dat> Printable <'dat> for TableBox <'tab> {
 	}
 
 }
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::border_box::TEST_BOX_STYLE;
+	use crate::grid::GridTarget;
+	use crate::measure::MeasureTarget;
+	use crate::plain::PlainTarget;
+
+	#[ test ]
+	fn table_row_links () {
+		let mut table = Table::builder ();
+		let mut row = table.row ();
+		row.right_link ("sda", "file:///dev/sda").space (2).left ("disk");
+		row.build ();
+		let table = table.build ();
+		let row = table.rows ().next ().unwrap ();
+		assert_eq! ("sda  disk", row.to_string ());
+		let mut target = PlainTarget::new_fmt (String::new (), 80);
+		target.push (& row);
+		target.newline ();
+		assert_eq! ("sda  disk <file:///dev/sda>\n", target.finish ().unwrap ());
+		let mut target = PlainTarget::new_fmt (String::new (), 80);
+		target.push (TableBox::new (TEST_BOX_STYLE, & table));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅▅▅▅▅▅▅▅\n",
+				"   sda  disk   <file:///dev/sda>\n",
+				" 🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄\n"),
+			target.finish ().unwrap ());
+	}
+
+	#[ test ]
+	fn table_box_measure () {
+		let mut table = Table::builder ();
+		let mut row = table.row ();
+		row.left ("Name").space (2).right ("Size");
+		row.build ();
+		table.separator ();
+		let table = table.build ();
+		assert_eq! (Measurement::fixed (10, 2), table.measure (80));
+		let table_box = TableBox::new (TEST_BOX_STYLE, & table);
+		let mut target = MeasureTarget::new (80);
+		target.push (TableBox::new (TEST_BOX_STYLE, & table));
+		assert_eq! (Measurement::fixed (target.used_width (), target.height ()), table_box.measure (80));
+	}
+
+	#[ test ]
+	fn table_box_snapshot () {
+		let mut table = Table::builder ();
+		let mut row = table.row ();
+		row.left ("Name").space (2).right ("Size");
+		row.build ();
+		table.separator ();
+		let mut row = table.row ();
+		row.left ("日本").space (2).right ("12");
+		row.build ();
+		let table = table.build ();
+		let mut target = GridTarget::new (20);
+		target.push (TableBox::new (TEST_BOX_STYLE, & table));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅▅▅▅▅▅▅▅▅\n",
+				"   Name  Size  \n",
+				" ──────────────\n",
+				"   日本    12  \n",
+				" 🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄🮄\n"),
+			target.to_text ());
+		assert_eq! (
+			concat! (
+				".AAAAAAAAAAAAAA\n",
+				".BBBBBBBBBBBBBB\n",
+				".CCCCCCCCCCCCCC\n",
+				".BBBBBBBBBBBBBB\n",
+				".AAAAAAAAAAAAAA\n",
+				"A: fg=#504030 bg=#000000\n",
+				"B: fg=#ffffff bg=#504030\n",
+				"C: fg=#000000 bg=#504030\n"),
+			target.to_style_map ());
+	}
+
+}
@@ -1,8 +1,11 @@
 use std::borrow::Cow;
+use std::cmp;
 use std::iter;
 
 use crate::{ Attr, Printable, Target };
 use crate::border_box::BoxStyle;
+use crate::measure::{ Measure, Measurement };
+use crate::width;
 
 pub struct BoxTabs <'dat> {
 	tabs: Vec <(Cow <'dat, str>, BoxStyle)>,
@@ -17,13 +20,27 @@ impl <'dat> BoxTabs <'dat> {
 
 }
 
+impl <'dat> Measure for BoxTabs <'dat> {
+
+	#[ inline ]
+	#[ allow (clippy::needless_borrowed_reference) ]
+	fn measure (& self, _max_width: usize) -> Measurement {
+		let width = self.tabs.iter ()
+			.map (|& (ref label, _)| width::str_width (label) + 5)
+			.sum ();
+		Measurement::fixed (width, 3)
+	}
+
+}
+
 impl <'tar> Printable <'tar> for BoxTabs <'tar> {
 
+	#[ allow (clippy::needless_borrowed_reference) ]
 	fn print (self, target: & mut impl Target <'tar>) {
 
 		let border_boxes: Vec <_> = self.tabs.iter ()
 			.map (|& (ref label, box_style)| {
-				box_style.with_width (label.chars ().count () + 4)
+				box_style.with_width (width::str_width (label) + 4)
 			})
 			.collect ();
 
@@ -67,6 +84,19 @@ impl <'dat> SlimTabs <'dat> {
 
 }
 
+impl <'dat> Measure for SlimTabs <'dat> {
+
+	#[ inline ]
+	#[ allow (clippy::needless_borrowed_reference) ]
+	fn measure (& self, max_width: usize) -> Measurement {
+		let width = self.tabs.iter ()
+			.map (|& (ref label, _)| width::str_width (label) + 3)
+			.sum ();
+		Measurement::new (width, cmp::max (width, max_width), 1)
+	}
+
+}
+
 impl <'tar> Printable <'tar> for SlimTabs <'tar> {
 
 	fn print (self, target: & mut impl Target <'tar>) {
@@ -74,7 +104,7 @@ impl <'tar> Printable <'tar> for SlimTabs <'tar> {
 		let mut pos = 0;
 
 		for (label, attr) in self.tabs {
-			pos += label.chars ().count () + 3;
+			pos += width::str_width (& label) + 3;
 			target.push (self.background);
 			target.push (" ");
 			target.push (attr);
@@ -94,3 +124,62 @@ impl <'tar> Printable <'tar> for SlimTabs <'tar> {
 	}
 
 }
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::Colour;
+	use crate::border_box::TEST_BOX_STYLE;
+	use crate::grid::GridTarget;
+
+	#[ test ]
+	fn box_tabs_snapshot () {
+		let mut target = GridTarget::new (20);
+		target.push (BoxTabs::new (vec! [
+			(Cow::Borrowed ("One"), TEST_BOX_STYLE),
+			(Cow::Borrowed ("日"), TEST_BOX_STYLE),
+		]));
+		assert_eq! (
+			concat! (
+				" ▅▅▅▅▅▅▅ ▅▅▅▅▅▅\n",
+				"   One     日  \n",
+				" 🮄🮄🮄🮄🮄🮄🮄 🮄🮄🮄🮄🮄🮄\n"),
+			target.to_text ());
+		assert_eq! (
+			concat! (
+				".AAAAAAA.AAAAAA\n",
+				".BBBBBBB.BBBBBB\n",
+				".AAAAAAA.AAAAAA\n",
+				"A: fg=#504030 bg=#000000\n",
+				"B: fg=#ffffff bg=#504030\n"),
+			target.to_style_map ());
+	}
+
+	#[ test ]
+	fn tabs_measure () {
+		let tabs = vec! [ (Cow::Borrowed ("One"), TEST_BOX_STYLE), (Cow::Borrowed ("日"), TEST_BOX_STYLE) ];
+		assert_eq! (Measurement::fixed (15, 3), BoxTabs::new (tabs).measure (80));
+		let tabs = vec! [ (Cow::Borrowed ("One"), Attr::default ()), (Cow::Borrowed ("日"), Attr::default ()) ];
+		assert_eq! (Measurement::new (11, 80, 1), SlimTabs::new (Attr::default (), tabs).measure (80));
+	}
+
+	#[ test ]
+	fn slim_tabs_snapshot () {
+		let mut target = GridTarget::new (16);
+		target.push (SlimTabs::new (
+			Attr::default ().background (Colour::new (0x20, 0x20, 0x20)),
+			vec! [
+				(Cow::Borrowed ("One"), Attr::default ().bold (true)),
+				(Cow::Borrowed ("Two"), Attr::default ()),
+			]));
+		assert_eq! ("  One   Two     \n", target.to_text ());
+		assert_eq! (
+			concat! (
+				"ABBBBBA.....AAAA\n",
+				"A: bg=#202020\n",
+				"B: bold\n"),
+			target.to_style_map ());
+	}
+
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{ Attr, Colour, colour, colour_names };
+use crate::border_box::BoxStyle;
+use crate::colour::{ ColoursMap, ColoursMapBuildError, ColoursMapDiagnostic, ColoursMapErrors };
+
+pub struct Theme {
+	colours: ColoursMap,
+	roles: HashMap <String, ThemeRole>,
+}
+
+#[ derive (Clone, Copy, Debug, Default, Eq, PartialEq) ]
+pub struct ThemeRole {
+	pub surround: Option <Colour>,
+	pub background: Option <Colour>,
+	pub foreground: Option <Colour>,
+	pub modifiers: Attr,
+}
+
+impl Theme {
+
+	pub fn load (path: impl AsRef <Path>) -> Result <Self, ThemeError> {
+		let source = fs::read_to_string (path).map_err (ThemeError::Io) ?;
+		Self::parse (& source)
+	}
+
+	pub fn parse (source: & str) -> Result <Self, ThemeError> {
+		let mut colour_entries: Vec <(usize, & str, & str)> = Vec::new ();
+		let mut role_entries: Vec <(usize, & str, & str, & str)> = Vec::new ();
+		let mut section: Option <& str> = None;
+		for (line_idx, line) in source.lines ().enumerate () {
+			let line_num = line_idx + 1;
+			let line = line.trim ();
+			if line.is_empty () || line.starts_with ('#') { continue }
+			if let Some (name) = line.strip_prefix ('[') {
+				let Some (name) = name.strip_suffix (']') else {
+					return Err (ThemeError::Syntax (line_num, line.to_owned ()));
+				};
+				section = Some (name.trim ());
+				continue;
+			}
+			let Some ((key, value)) = line.split_once ('=') else {
+				return Err (ThemeError::Syntax (line_num, line.to_owned ()));
+			};
+			let (key, value) = (key.trim (), value.trim ());
+			match section {
+				None => return Err (ThemeError::Syntax (line_num, line.to_owned ())),
+				Some ("colours") => colour_entries.push ((line_num, key, value)),
+				Some (role) => role_entries.push ((line_num, role, key, value)),
+			}
+		}
+		let colours = ColoursMap::build_with_lines (colour_entries).map_err (ThemeError::Colours) ?;
+		let mut roles: HashMap <String, ThemeRole> = HashMap::new ();
+		for (line_num, role_name, key, value) in role_entries {
+			let role = roles.entry (role_name.to_owned ()).or_default ();
+			let colour = || resolve_colour (& colours, role_name, key, value)
+				.map_err (|error| ThemeError::Colours (ColoursMapErrors {
+					errors: vec! [ ColoursMapDiagnostic { layer: None, line: Some (line_num), error } ],
+				}));
+			match key {
+				"surround" => role.surround = Some (colour () ?),
+				"background" => role.background = Some (colour () ?),
+				"foreground" => role.foreground = Some (colour () ?),
+				"modifiers" => {
+					for modifier in value.split_whitespace () {
+						role.modifiers = role.modifiers.modifier (modifier)
+							.ok_or_else (|| ThemeError::InvalidModifier (line_num, modifier.to_owned ())) ?;
+					}
+				},
+				_ => return Err (ThemeError::UnknownKey (line_num, key.to_owned ())),
+			}
+		}
+		Ok (Self { colours, roles })
+	}
+
+	#[ inline ]
+	pub fn colours (& self) -> & ColoursMap {
+		& self.colours
+	}
+
+	#[ inline ]
+	pub fn role (& self, name: & str) -> Option <& ThemeRole> {
+		self.roles.get (name)
+	}
+
+	pub fn box_style (& self, name: & str) -> Option <BoxStyle> {
+		let role = self.role (name) ?;
+		Some (BoxStyle::new (role.surround ?, role.background ?, role.foreground ?))
+	}
+
+	pub fn attr (& self, name: & str) -> Option <Attr> {
+		let role = self.role (name) ?;
+		let mut attr = role.modifiers;
+		if let Some (foreground) = role.foreground { attr = attr.foreground (foreground) }
+		if let Some (background) = role.background { attr = attr.background (background) }
+		Some (attr)
+	}
+
+}
+
+fn resolve_colour (
+	colours: & ColoursMap,
+	role: & str,
+	key: & str,
+	value: & str,
+) -> Result <Colour, ColoursMapBuildError> {
+	let name = format! ("{role}.{key}");
+	if colour::is_literal (value) {
+		return value.parse ().map_err (|err| ColoursMapBuildError::ParseError (name, err));
+	}
+	colours.get (value).copied ()
+		.or_else (|| colour_names::lookup (value))
+		.ok_or_else (|| ColoursMapBuildError::InvalidReference (name, value.to_owned ()))
+}
+
+#[ derive (Debug, thiserror::Error) ]
+pub enum ThemeError {
+	#[ error ("Error reading theme") ]
+	Io (#[ source ] io::Error),
+	#[ error ("Syntax error on line {0}: {1}") ]
+	Syntax (usize, String),
+	#[ error ("Unknown key on line {0}: {1}") ]
+	UnknownKey (usize, String),
+	#[ error ("Invalid modifier on line {0}: {1}") ]
+	InvalidModifier (usize, String),
+	#[ error ("Colour errors:\n{0}") ]
+	Colours (#[ source ] ColoursMapErrors),
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+	use crate::colour::ParseColourError;
+
+	const THEME: & str = "
+		# Example theme
+		[colours]
+		black = #000000
+		white = #ffffff
+		red = #c02020
+		error = red
+
+		[error-box]
+		surround = black
+		background = error
+		foreground = white
+
+		[active-tab]
+		foreground = #ff0
+		modifiers = bold underline=curly
+	";
+
+	#[ test ]
+	fn theme_parse () -> anyhow::Result <()> {
+		let theme = Theme::parse (THEME) ?;
+		assert_eq! (4, theme.colours ().len ());
+		let box_style = theme.box_style ("error-box").unwrap ();
+		assert_eq! (Colour::new (0x00, 0x00, 0x00), box_style.surround);
+		assert_eq! (Colour::new (0xc0, 0x20, 0x20), box_style.background);
+		assert_eq! (Colour::new (0xff, 0xff, 0xff), box_style.foreground);
+		assert_eq! (None, theme.box_style ("active-tab"));
+		assert_eq! (
+			Some (Attr::default ()
+				.foreground (Colour::new (0xff, 0xff, 0x00))
+				.bold (true)
+				.underline (crate::Underline::Curly)),
+			theme.attr ("active-tab"));
+		assert_eq! (None, theme.attr ("missing"));
+		Ok (())
+	}
+
+	#[ test ]
+	fn theme_errors () {
+		let parse = |source| Theme::parse (source).err ().unwrap ();
+		assert! (matches! (parse ("x = y"), ThemeError::Syntax (1, line) if line == "x = y"));
+		assert! (matches! (parse ("\n[role"), ThemeError::Syntax (2, line) if line == "[role"));
+		assert! (matches! (parse ("[a]\ncolour = #fff"), ThemeError::UnknownKey (2, key) if key == "colour"));
+		assert! (matches! (
+			parse ("[a]\nmodifiers = bold shiny"),
+			ThemeError::InvalidModifier (2, modifier) if modifier == "shiny"));
+		let colours_errors = |line, error| ColoursMapErrors {
+			errors: vec! [ ColoursMapDiagnostic { layer: None, line: Some (line), error } ],
+		};
+		assert! (matches! (
+			parse ("[a]\nforeground = bleu"),
+			ThemeError::Colours (errors) if errors == colours_errors (
+				2,
+				ColoursMapBuildError::InvalidReference ("a.foreground".to_owned (), "bleu".to_owned ()))));
+		assert! (matches! (
+			parse ("[a]\nsurround = #xyz"),
+			ThemeError::Colours (errors) if errors == colours_errors (
+				2,
+				ColoursMapBuildError::ParseError ("a.surround".to_owned (), ParseColourError::ValueParse))));
+		assert! (matches! (
+			parse ("\n[colours]\na = b"),
+			ThemeError::Colours (errors) if errors == colours_errors (
+				3,
+				ColoursMapBuildError::InvalidReference ("a".to_owned (), "b".to_owned ()))));
+		assert_eq! (
+			"Colour errors:\n\
+				line 2: Invalid colour name for a: b\n\
+				line 4: Circular reference: c → d → c",
+			parse ("[colours]\na = b\nok = #fff\nc = d\nd = c").to_string ());
+		assert_eq! (
+			"Colour errors:\nline 2: Parse error for a.surround: invalid hex value",
+			parse ("[a]\nsurround = #xyz").to_string ());
+		assert! (matches! (
+			Theme::load ("/nonexistent/theme.ini"),
+			Err (ThemeError::Io (err)) if err.kind () == io::ErrorKind::NotFound));
+	}
+
+}
@@ -0,0 +1,49 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+#[ inline ]
+pub fn str_width (text: & str) -> usize {
+	text.graphemes (true).map (grapheme_width).sum ()
+}
+
+#[ inline ]
+pub fn grapheme_width (grapheme: & str) -> usize {
+	if grapheme.chars ().next ().is_some_and (char::is_control) { return 0 }
+	grapheme.width ().min (2)
+}
+
+pub fn split_at_width (text: & str, max_width: usize) -> (usize, usize) {
+	let mut width = 0;
+	for (pos, grapheme) in text.grapheme_indices (true) {
+		let grapheme_width = grapheme_width (grapheme);
+		if width + grapheme_width > max_width { return (pos, width) }
+		width += grapheme_width;
+	}
+	(text.len (), width)
+}
+
+#[ cfg (test) ]
+mod tests {
+
+	use super::*;
+
+	#[ test ]
+	fn str_width_unicode () {
+		assert_eq! (5, str_width ("hello"));
+		assert_eq! (4, str_width ("日本"));
+		assert_eq! (1, str_width ("e\u{301}"));
+		assert_eq! (2, str_width ("👍"));
+		assert_eq! (2, str_width ("👍🏽"));
+		assert_eq! (1, str_width ("▅"));
+		assert_eq! (0, str_width ("\t"));
+	}
+
+	#[ test ]
+	fn split_at_width_graphemes () {
+		assert_eq! ((3, 3), split_at_width ("hello", 3));
+		assert_eq! ((6, 4), split_at_width ("日本語", 5));
+		assert_eq! ((4, 2), split_at_width ("e\u{301}x", 2));
+		assert_eq! ((5, 5), split_at_width ("hello", 10));
+	}
+
+}